@@ -171,13 +171,13 @@ function App() {
 
   const runForecast = async (indicator: string) => {
     const originalSeries = response.series[indicator];
-    if (!originalSeries || originalSeries.length < 2) return;
+    if (!originalSeries || originalSeries.length < 3) return;
 
     setIsForecasting(indicator);
     try {
       const validSeries = originalSeries.filter((p: SeriesPoint) => p.value !== null && p.value !== undefined);
       
-      if (validSeries.length < 2) {
+      if (validSeries.length < 3) {
         throw new Error('Not enough valid data points for forecasting');
       }
 
@@ -583,7 +583,7 @@ function App() {
                 <button 
                   onClick={() => runForecast(indicator)} 
                   className="ask-button" 
-                  disabled={isForecasting === indicator || !response.series[indicator] || response.series[indicator].length < 2}
+                  disabled={isForecasting === indicator || !response.series[indicator] || response.series[indicator].length < 3}
                 >
                   {isForecasting === indicator ? 'Forecasting..' : `Forecast ${forecastYears} Years`}
                 </button>
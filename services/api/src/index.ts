@@ -26,6 +26,12 @@ const indicatorMap: Record<string, string> = {
   capita: 'gdp_per_capita'
 };
 
+const correlationMethods: Record<string, compute.CorrelationMethod> = {
+  pearson: compute.CorrelationMethod.Pearson,
+  spearman: compute.CorrelationMethod.Spearman,
+  kendall: compute.CorrelationMethod.Kendall
+};
+
 // --- HELPER FUNCTIONS ---
 function getNodeLabel(geoCode: string): string {
   if (geoCode === 'IN') return 'Country';
@@ -34,6 +40,16 @@ function getNodeLabel(geoCode: string): string {
   return 'Country'; // Default fallback
 }
 
+// Input the compute module rejects is the caller's fault, not ours: answer
+// with a 400 carrying its code and argument instead of a 500.
+function sendError(res: Response, e: any, endpoint: string) {
+  if (e?.name === 'ComputeError') {
+    return res.status(400).json({ error: e.message, code: e.code, arg: e.arg });
+  }
+  console.error(`${endpoint} endpoint error:`, e);
+  return res.status(500).json({ error: e.message || String(e) });
+}
+
 // --- API ENDPOINTS ---
 
 app.get('/health', (_req: Request, res: Response) => {
@@ -142,38 +158,24 @@ app.post('/ask', async (req: Request, res: Response) => {
       }));
 
       seriesByIndicator[indicator] = series;
-      
-      if (series.length > 1) {
-        const values = new Float64Array(series.map(p => p.value));
-        summaryByIndicator[indicator] = {
-          mean: compute.mean(values),
-          pct_change: compute.pct_change(values),
-          slope: compute.slope(values),
-          count: series.length,
-          latest: series[series.length - 1]?.value || null,
-          earliest: series[0]?.value || null
-        };
-      } else if (series.length === 1) {
-        summaryByIndicator[indicator] = {
-          mean: series[0].value,
-          pct_change: 0,
-          slope: 0,
-          count: 1,
-          latest: series[0].value,
-          earliest: series[0].value
-        };
-      } else {
-        summaryByIndicator[indicator] = {
-          mean: null,
-          pct_change: null,
-          slope: null,
-          count: 0,
-          latest: null,
-          earliest: null
-        };
-      }
     }
 
+    // Summarize every indicator in one WASM call.
+    const allSeries = indicators.map(ind => seriesByIndicator[ind]);
+    const summaries = compute.summarize_batch(
+      new Float64Array(allSeries.flatMap(series => series.map(p => p.year))),
+      new Float64Array(allSeries.flatMap(series => series.map(p => p.value))),
+      new Uint32Array(allSeries.map(series => series.length))
+    );
+    indicators.forEach((indicator, i) => {
+      const summary = summaries[i];
+      summaryByIndicator[indicator] = {
+        ...summary,
+        latest: summary.last,
+        earliest: summary.first
+      };
+    });
+
     await session.close();
     res.json({ 
       question, 
@@ -184,21 +186,24 @@ app.post('/ask', async (req: Request, res: Response) => {
     });
 
   } catch (e: any) {
-    console.error('Ask endpoint error:', e);
-    res.status(500).json({ error: e.message || String(e) });
+    sendError(res, e, 'Ask');
   }
 });
 
 app.post('/compare', async (req: Request, res: Response) => {
   try {
-    const { indicators, geoCode = 'IN' } = req.body ?? {};
+    const { indicators, geoCode = 'IN', method = 'pearson' } = req.body ?? {};
     if (!Array.isArray(indicators) || indicators.length !== 2) {
       return res.status(400).json({ error: 'Please provide exactly 2 indicators' });
     }
+    const correlationMethod = correlationMethods[method];
+    if (correlationMethod === undefined) {
+      return res.status(400).json({ error: `Unknown correlation method: ${method}` });
+    }
     
     const nodeLabel = getNodeLabel(geoCode);
     const session = getNeo4jSession();
-    const seriesData: Record<string, number[]> = {};
+    const seriesData: Record<string, { year: number; value: number }[]> = {};
 
     for (const indicator of indicators) {
       const result = await session.run(
@@ -210,37 +215,122 @@ app.post('/compare', async (req: Request, res: Response) => {
         `,
         { indicator, geoCode }
       );
-      seriesData[indicator] = result.records.map(r => Number(r.get('value')));
+      seriesData[indicator] = result.records.map(r => ({
+        year: r.get('year').toNumber ? r.get('year').toNumber() : r.get('year'),
+        value: Number(r.get('value')),
+      }));
     }
     await session.close();
 
-    if (seriesData[indicators[0]].length !== seriesData[indicators[1]].length) {
-      return res.status(400).json({ 
-        error: 'Cannot compare indicators with different numbers of data points.',
-        details: {
-          [indicators[0]]: seriesData[indicators[0]].length,
-          [indicators[1]]: seriesData[indicators[1]].length
-        }
-      });
-    }
+    const [first, second] = indicators.map((ind: string) => seriesData[ind]);
+    const toYears = (s: { year: number }[]) => new Float64Array(s.map(p => p.year));
+    const toValues = (s: { value: number }[]) => new Float64Array(s.map(p => p.value));
+
+    // Correlate only the years both indicators cover.
+    const aligned = compute.align_series(
+      toYears(first), toValues(first),
+      toYears(second), toValues(second),
+      compute.Join.Inner
+    );
 
-    if (seriesData[indicators[0]].length === 0) {
-      return res.status(400).json({ error: 'No data found for the specified indicators and geography.' });
+    if (aligned.years.length === 0) {
+      return res.status(400).json({ error: 'No overlapping years found for the specified indicators and geography.' });
     }
 
-    const a = new Float64Array(seriesData[indicators[0]]);
-    const b = new Float64Array(seriesData[indicators[1]]);
-    const corr = compute.pearson(a, b);
+    const corr = compute.correlation_years(
+      toYears(first), toValues(first),
+      toYears(second), toValues(second),
+      compute.Join.Inner,
+      correlationMethod
+    );
+
+    // Too few overlapping years for a significance test; report the bare coefficient.
+    const significance = aligned.years.length >= 4
+      ? compute.correlation_test_years(
+          toYears(first), toValues(first),
+          toYears(second), toValues(second),
+          compute.Join.Inner,
+          correlationMethod,
+          0.95
+        )
+      : null;
+    // Flags correlations that only reflect both indicators trending over time.
+    const trend = aligned.years.length >= 4
+      ? compute.trend_correlation(
+          toYears(first), toValues(first),
+          toYears(second), toValues(second),
+          compute.Join.Inner
+        )
+      : null;
     
     res.json({ 
       indicators, 
       geoCode,
+      method,
       correlation: corr,
-      dataPoints: a.length
+      significance,
+      trend,
+      dataPoints: aligned.years.length
     });
   } catch (e: any) {
-    console.error('Compare endpoint error:', e);
-    res.status(500).json({ error: e.message || String(e) });
+    sendError(res, e, 'Compare');
+  }
+});
+
+app.post('/correlation-matrix', async (req: Request, res: Response) => {
+  try {
+    const { indicators, geoCode = 'IN', method = 'pearson' } = req.body ?? {};
+    if (!Array.isArray(indicators) || indicators.length < 2) {
+      return res.status(400).json({ error: 'Please provide at least 2 indicators' });
+    }
+    const correlationMethod = correlationMethods[method];
+    if (correlationMethod === undefined) {
+      return res.status(400).json({ error: `Unknown correlation method: ${method}` });
+    }
+
+    const nodeLabel = getNodeLabel(geoCode);
+    const session = getNeo4jSession();
+    const seriesData: Record<string, Map<number, number>> = {};
+
+    for (const indicator of indicators) {
+      const result = await session.run(
+        `
+          MATCH (g:${nodeLabel} {code: $geoCode})<-[:MEASURED_IN]-(s:Series {indicator: $indicator})
+          WHERE s.value IS NOT NULL
+          RETURN s.year AS year, s.value AS value
+          ORDER BY year
+        `,
+        { indicator, geoCode }
+      );
+      seriesData[indicator] = new Map(result.records.map(r => [
+        r.get('year').toNumber ? r.get('year').toNumber() : r.get('year'),
+        Number(r.get('value'))
+      ]));
+    }
+    await session.close();
+
+    // Keep only the years every indicator covers.
+    const years = [...seriesData[indicators[0]].keys()]
+      .filter(year => indicators.every((ind: string) => seriesData[ind].has(year)))
+      .sort((a, b) => a - b);
+
+    if (years.length < 4) {
+      return res.status(400).json({ error: 'At least 4 overlapping years are needed for a correlation matrix.' });
+    }
+
+    const values = new Float64Array(
+      indicators.flatMap((ind: string) => years.map(year => seriesData[ind].get(year) as number))
+    );
+    const matrix = compute.correlation_matrix(values, indicators.length, correlationMethod);
+
+    res.json({
+      indicators,
+      geoCode,
+      years,
+      ...matrix
+    });
+  } catch (e: any) {
+    sendError(res, e, 'Correlation-matrix');
   }
 });
 
@@ -301,43 +391,113 @@ app.post('/compare-series', async (req: Request, res: Response) => {
 
 app.post('/forecast', async (req: Request, res: Response) => {
   try {
-    const { series, forecastYears } = req.body;
-    if (!series || !forecastYears || series.length < 2) {
+    const { series, forecastYears, model = 'linear', bounds = [0, 100] } = req.body;
+    if (!series || !forecastYears || series.length < 3) {
       return res.status(400).json({ 
-        error: 'Missing series data or years to forecast. A minimum of two data points is required.' 
+        error: 'Missing series data or years to forecast. A minimum of three data points is required.' 
       });
     }
+    if (!Number.isInteger(forecastYears) || forecastYears < 1 || forecastYears > 50) {
+      return res.status(400).json({ error: 'forecastYears must be a whole number from 1 to 50' });
+    }
+    if (model !== 'linear' && model !== 'auto' && model !== 'bounded') {
+      return res.status(400).json({ error: `Unknown forecast model: ${model}` });
+    }
+    if (model === 'bounded' && !(
+      Array.isArray(bounds) && bounds.length === 2 &&
+      bounds.every((b: unknown) => typeof b === 'number' && Number.isFinite(b)) &&
+      bounds[0] < bounds[1]
+    )) {
+      return res.status(400).json({ error: 'bounds must be two finite numbers [lo, hi] with lo < hi' });
+    }
 
+    type ForecastPoint = {
+      year: number;
+      point: number;
+      lo80: number;
+      hi80: number;
+      lo95: number;
+      hi95: number;
+    };
+    const years = new Float64Array(series.map((p: any) => p.year));
     const values = new Float64Array(series.map((p: any) => p.value));
-    const slope = compute.slope(values);
-    
-    const lastPoint = series[series.length - 1];
-    const forecast = [];
-
-    for (let i = 1; i <= forecastYears; i++) {
-      forecast.push({
-        year: lastPoint.year + i,
-        value: null, // Historical value is null
-        forecastValue: lastPoint.value + (slope * i),
-        isForcast: true
-      });
+    const slope = compute.slope_xy(years, values);
+    let forecast: ForecastPoint[];
+    let order: { p: number; d: number; q: number } | undefined;
+    if (model === 'auto') {
+      const fit = compute.auto_arima(years, values, 3, 3, forecastYears);
+      forecast = fit.forecast;
+      order = { p: fit.p, d: fit.d, q: fit.q };
+    } else if (model === 'bounded') {
+      // Logit-scale trend keeps rates and percentages inside their bounds.
+      const [lo, hi] = bounds;
+      forecast = compute.logit_forecast(years, values, lo, hi, forecastYears) as ForecastPoint[];
+    } else {
+      forecast = compute.forecast(years, values, forecastYears) as ForecastPoint[];
     }
-    
+
     const fullSeries = series.map((p: any) => ({ 
       ...p, 
       forecastValue: p.value,
       isForcast: false 
-    })).concat(forecast);
+    })).concat(forecast.map(f => ({
+      ...f,
+      value: null, // Historical value is null
+      forecastValue: f.point,
+      isForcast: true
+    })));
 
     res.json({ 
       series: fullSeries,
       slope,
-      baseValue: lastPoint.value,
+      model,
+      order,
+      forecast,
       forecastYears
     });
   } catch (e: any) {
-    console.error('Forecast endpoint error:', e);
-    res.status(500).json({ error: e.message || String(e) });
+    sendError(res, e, 'Forecast');
+  }
+});
+
+app.post('/backtest', async (req: Request, res: Response) => {
+  try {
+    const { series, maxHorizon = 3, minTrain } = req.body;
+    if (!series || series.length < 6) {
+      return res.status(400).json({ error: 'Missing series data. A minimum of six data points is required.' });
+    }
+
+    const years = new Float64Array(series.map((p: any) => p.year));
+    const values = new Float64Array(series.map((p: any) => p.value));
+    const train = minTrain ?? Math.max(5, Math.floor(series.length / 2));
+    const methods = {
+      naive: compute.ForecastMethod.Naive,
+      linear: compute.ForecastMethod.Linear,
+      ses: compute.ForecastMethod.SimpleSmoothing,
+      holt: compute.ForecastMethod.Holt,
+      damped: compute.ForecastMethod.DampedTrend,
+      auto: compute.ForecastMethod.AutoArima
+    };
+
+    // Methods that cannot train on the shortest window are reported, not fatal.
+    const results: Record<string, any> = {};
+    for (const [name, method] of Object.entries(methods)) {
+      try {
+        results[name] = compute.backtest(years, values, method, train, maxHorizon);
+      } catch (e: any) {
+        results[name] = { error: e.message || String(e) };
+      }
+    }
+
+    // Best by one-step MASE among methods that produced one; null if none did.
+    const oneStepMase = (name: string): number => results[name].horizons?.[0]?.mase ?? NaN;
+    const best = Object.keys(methods)
+      .filter(name => Number.isFinite(oneStepMase(name)))
+      .reduce<string | null>((a, b) => (a === null || oneStepMase(b) < oneStepMase(a) ? b : a), null);
+
+    res.json({ minTrain: train, maxHorizon, best, results });
+  } catch (e: any) {
+    sendError(res, e, 'Backtest');
   }
 });
 
@@ -406,7 +566,9 @@ app.listen(port, () => {
   console.log(`   GET  /indicators - Available indicators`);
   console.log(`   POST /ask - Natural language queries`);
   console.log(`   POST /compare - Compare two indicators`);
+  console.log(`   POST /correlation-matrix - Correlate many indicators`);
   console.log(`   POST /compare-series - Compare series data`);
   console.log(`   POST /forecast - Forecast future values`);
+  console.log(`   POST /backtest - Compare forecast accuracy by method`);
   console.log(`   POST /multi-geo - Multi-geography analysis`);
 });
\ No newline at end of file
@@ -0,0 +1,160 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::series::YearSeries;
+
+/// How two year series are lined up before a two-series statistic.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Join {
+    /// Keep only years present in both series.
+    Inner = 0,
+    /// Keep every year present in either series; missing values are NaN.
+    Outer = 1,
+    /// Outer join, carrying the last observed value forward into gaps.
+    /// Years before a series' first observation stay NaN.
+    ForwardFill = 2,
+    /// Outer join, linearly interpolating gaps between observed years.
+    /// Years outside a series' observed range stay NaN.
+    Interpolate = 3,
+}
+
+/// Two series placed on a common year axis.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Aligned {
+    pub years: Vec<f64>,
+    pub a: Vec<f64>,
+    pub b: Vec<f64>,
+}
+
+impl Aligned {
+    /// Drop rows where either side is NaN, leaving only usable pairs.
+    pub fn complete(self) -> Aligned {
+        let mut out = Aligned::default();
+        for ((year, a), b) in self.years.into_iter().zip(self.a).zip(self.b) {
+            if !a.is_nan() && !b.is_nan() {
+                out.years.push(year);
+                out.a.push(a);
+                out.b.push(b);
+            }
+        }
+        out
+    }
+
+    pub fn len(&self) -> usize {
+        self.years.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.years.is_empty()
+    }
+}
+
+/// Align two series on year according to `join`.
+pub fn align(a: &YearSeries, b: &YearSeries, join: Join) -> Aligned {
+    let years = match join {
+        Join::Inner => a
+            .years()
+            .iter()
+            .copied()
+            .filter(|&y| b.get(y).is_some())
+            .collect(),
+        Join::Outer | Join::ForwardFill | Join::Interpolate => union_years(a, b),
+    };
+    let a = years.iter().map(|&y| lookup(a, y, join)).collect();
+    let b = years.iter().map(|&y| lookup(b, y, join)).collect();
+    Aligned { years, a, b }
+}
+
+fn union_years(a: &YearSeries, b: &YearSeries) -> Vec<f64> {
+    let mut years: Vec<f64> = a.years().iter().chain(b.years()).copied().collect();
+    years.sort_by(f64::total_cmp);
+    years.dedup();
+    years
+}
+
+fn lookup(series: &YearSeries, year: f64, join: Join) -> f64 {
+    let years = series.years();
+    let values = series.values();
+    match years.binary_search_by(|y| y.total_cmp(&year)) {
+        Ok(i) => values[i],
+        // `i` is the insertion point: years[i - 1] < year < years[i].
+        Err(i) => match join {
+            Join::Inner | Join::Outer => f64::NAN,
+            Join::ForwardFill if i > 0 => values[i - 1],
+            Join::Interpolate if i > 0 && i < years.len() => {
+                let (x0, x1) = (years[i - 1], years[i]);
+                let (y0, y1) = (values[i - 1], values[i]);
+                y0 + (y1 - y0) * (year - x0) / (x1 - x0)
+            }
+            _ => f64::NAN,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Offset ranges with gaps on both sides.
+    fn pair() -> (YearSeries, YearSeries) {
+        let a = YearSeries::new(&[2000.0, 2002.0, 2005.0], &[10.0, 14.0, 20.0]);
+        let b = YearSeries::new(&[2002.0, 2003.0, 2006.0, 2008.0], &[1.0, 2.0, 5.0, 9.0]);
+        (a, b)
+    }
+
+    #[track_caller]
+    fn assert_values(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len(), "{actual:?} != {expected:?}");
+        for (&x, &e) in actual.iter().zip(expected) {
+            assert!(
+                (x.is_nan() && e.is_nan()) || (x - e).abs() < 1e-12,
+                "{actual:?} != {expected:?}"
+            );
+        }
+    }
+
+    const NAN: f64 = f64::NAN;
+    const UNION: [f64; 6] = [2000.0, 2002.0, 2003.0, 2005.0, 2006.0, 2008.0];
+
+    #[test]
+    fn inner_keeps_shared_years() {
+        let (a, b) = pair();
+        let aligned = align(&a, &b, Join::Inner);
+        assert_values(&aligned.years, &[2002.0]);
+        assert_values(&aligned.a, &[14.0]);
+        assert_values(&aligned.b, &[1.0]);
+    }
+
+    #[test]
+    fn outer_leaves_gaps_nan() {
+        let (a, b) = pair();
+        let aligned = align(&a, &b, Join::Outer);
+        assert_values(&aligned.years, &UNION);
+        assert_values(&aligned.a, &[10.0, 14.0, NAN, 20.0, NAN, NAN]);
+        assert_values(&aligned.b, &[NAN, 1.0, 2.0, NAN, 5.0, 9.0]);
+        assert_values(&aligned.complete().years, &[2002.0]);
+    }
+
+    #[test]
+    fn forward_fill_carries_last_value() {
+        let (a, b) = pair();
+        let aligned = align(&a, &b, Join::ForwardFill);
+        assert_values(&aligned.years, &UNION);
+        assert_values(&aligned.a, &[10.0, 14.0, 14.0, 20.0, 20.0, 20.0]);
+        assert_values(&aligned.b, &[NAN, 1.0, 2.0, 2.0, 5.0, 9.0]);
+    }
+
+    #[test]
+    fn interpolate_fills_inside_observed_range() {
+        let (a, b) = pair();
+        let aligned = align(&a, &b, Join::Interpolate);
+        assert_values(&aligned.years, &UNION);
+        assert_values(&aligned.a, &[10.0, 14.0, 16.0, 20.0, NAN, NAN]);
+        assert_values(&aligned.b, &[NAN, 1.0, 2.0, 4.0, 5.0, 9.0]);
+        let complete = aligned.complete();
+        assert_values(&complete.years, &[2002.0, 2003.0, 2005.0]);
+        assert_values(&complete.a, &[14.0, 16.0, 20.0]);
+        assert_values(&complete.b, &[1.0, 2.0, 4.0]);
+    }
+}
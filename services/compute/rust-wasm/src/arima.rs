@@ -0,0 +1,443 @@
+//! ARIMA models fitted by conditional sum of squares.
+
+use serde::Serialize;
+
+use crate::criteria::{aic, aicc, bic, gaussian_log_lik};
+use crate::error::{require_len, require_range, require_regular, ComputeError};
+use crate::forecast::ForecastPoint;
+use crate::optimize::nelder_mead;
+use crate::series::YearSeries;
+use crate::stationarity::{self, Deterministic};
+
+/// Most differences automatic selection will take.
+const MAX_D: usize = 2;
+
+/// A fitted ARIMA(p, d, q) model and its forecasts.
+#[derive(Debug, Clone, Serialize)]
+pub struct ArimaFit {
+    pub p: usize,
+    pub d: usize,
+    pub q: usize,
+    /// AR coefficients on the differenced series, lag 1 first.
+    pub ar: Vec<f64>,
+    /// MA coefficients, lag 1 first, with the sign convention
+    /// `e_t + theta_1 e_{t-1} + ...`.
+    pub ma: Vec<f64>,
+    /// Mean of the differenced series: the level for d = 0, the drift per
+    /// step for d = 1. `None` for d = 2, which never has one.
+    pub mean: Option<f64>,
+    pub sse: f64,
+    /// Innovation standard deviation used for the intervals.
+    pub sigma: f64,
+    /// Conditional (CSS) log-likelihood.
+    pub log_lik: f64,
+    pub aic: f64,
+    pub aicc: f64,
+    pub bic: f64,
+    /// One-step errors on the differenced series, for every step after
+    /// the ones it conditions on: the first `p`, or for `auto_arima` the
+    /// leading points shared by every candidate.
+    pub residuals: Vec<f64>,
+    /// Observations entering the sum of squares.
+    pub n: usize,
+    pub forecast: Vec<ForecastPoint>,
+}
+
+/// Fit ARIMA(p, d, q) to an evenly spaced series and forecast `horizon`
+/// steps ahead.
+///
+/// Coefficients minimize the conditional sum of squares, starting from
+/// zero pre-sample errors, and are constrained to the stationary and
+/// invertible region. A mean is estimated for d of 0 or 1.
+pub fn arima(
+    series: &YearSeries,
+    p: usize,
+    d: usize,
+    q: usize,
+    horizon: usize,
+) -> Result<ArimaFit, ComputeError> {
+    require_range("d", d as f64, 0.0, MAX_D as f64)?;
+    require_range("horizon", horizon as f64, 1.0, f64::INFINITY)?;
+    require_regular("years", series.years())?;
+    let spec = Spec { p, d, q };
+    require_len("values", series.values(), spec.min_len(p))?;
+    fit(series, spec, p, horizon)
+}
+
+/// Choose the ARIMA order automatically and fit it.
+///
+/// As in Hyndman and Khandakar's auto.arima, the series is differenced
+/// until a KPSS test no longer rejects stationarity at 5% (at most
+/// twice); then every p up to `max_p` and q up to `max_q` that the sample
+/// supports is fitted and the lowest AICc wins.
+///
+/// Every candidate conditions on the same leading observations, the most
+/// any candidate's AR lags need, so all criteria are computed on one
+/// common sample and the choice does not depend on the units of the data.
+pub fn auto_arima(
+    series: &YearSeries,
+    max_p: usize,
+    max_q: usize,
+    horizon: usize,
+) -> Result<ArimaFit, ComputeError> {
+    require_range("horizon", horizon as f64, 1.0, f64::INFINITY)?;
+    require_regular("years", series.years())?;
+    // KPSS needs 8 points after the last possible difference.
+    require_len("values", series.values(), 8 + MAX_D)?;
+
+    let mut d = 0;
+    let mut w = series.values().to_vec();
+    while d < MAX_D && !stationarity::kpss_values(&w, Deterministic::Constant, None)?.stationary {
+        w = difference(&w, 1);
+        d += 1;
+    }
+
+    // Condition on as many points as the longest AR order the sample
+    // supports on its own.
+    let n = series.len();
+    let condition = (0..=max_p)
+        .rev()
+        .find(|&p| n >= Spec { p, d, q: 0 }.min_len(p))
+        .ok_or(ComputeError::TooShort {
+            arg: "values",
+            min: Spec { p: 0, d, q: 0 }.min_len(0),
+            len: n,
+        })?;
+    let mut best: Option<ArimaFit> = None;
+    for p in 0..=condition {
+        for q in 0..=max_q {
+            let spec = Spec { p, d, q };
+            if n < spec.min_len(condition) {
+                continue;
+            }
+            let candidate = fit(series, spec, condition, horizon)?;
+            if best.as_ref().is_none_or(|b| candidate.aicc < b.aicc) {
+                best = Some(candidate);
+            }
+        }
+    }
+    best.ok_or(ComputeError::TooShort {
+        arg: "values",
+        min: Spec { p: 0, d, q: 0 }.min_len(0),
+        len: n,
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Spec {
+    p: usize,
+    d: usize,
+    q: usize,
+}
+
+impl Spec {
+    fn has_mean(self) -> bool {
+        self.d < 2
+    }
+
+    /// Estimated parameters, counting the innovation variance.
+    fn n_params(self) -> usize {
+        self.p + self.q + usize::from(self.has_mean()) + 1
+    }
+
+    /// Shortest series leaving AICc defined when the sum of squares
+    /// starts `condition` steps into the differenced series.
+    fn min_len(self, condition: usize) -> usize {
+        self.d + condition + self.n_params() + 2
+    }
+}
+
+/// Fit `spec` by CSS, conditioning on the first `condition` differenced
+/// values; `condition` must be at least `spec.p`.
+fn fit(
+    series: &YearSeries,
+    spec: Spec,
+    condition: usize,
+    horizon: usize,
+) -> Result<ArimaFit, ComputeError> {
+    let Spec { p, d, q } = spec;
+    let y = series.values();
+    let w = difference(y, d);
+    let center = w.iter().sum::<f64>() / w.len() as f64;
+    let scale = (w.iter().map(|v| (v - center).powi(2)).sum::<f64>() / w.len() as f64)
+        .sqrt()
+        .max(f64::MIN_POSITIVE);
+
+    // Optimize in unconstrained coordinates: partial autocorrelations
+    // through tanh for AR and MA, and the mean in units of the spread.
+    let unpack = |u: &[f64]| -> (Vec<f64>, Vec<f64>, f64) {
+        let ar = from_partials(&u[..p]);
+        let ma: Vec<f64> = from_partials(&u[p..p + q]).iter().map(|c| -c).collect();
+        let mean = if spec.has_mean() {
+            center + scale * u[p + q]
+        } else {
+            0.0
+        };
+        (ar, ma, mean)
+    };
+    let objective = |u: &[f64]| {
+        let (ar, ma, mean) = unpack(u);
+        let sse = css(&w, &ar, &ma, mean, condition).1;
+        if sse.is_finite() {
+            sse
+        } else {
+            f64::INFINITY
+        }
+    };
+    let dim = p + q + usize::from(spec.has_mean());
+    let mut u = vec![0.0; dim];
+    if dim > 0 {
+        // A restart from the first optimum guards against early collapse
+        // of the simplex.
+        for _ in 0..2 {
+            u = nelder_mead(objective, &u, 0.5, 400 * dim);
+        }
+    }
+    let (ar, ma, mean) = unpack(&u);
+    let (residuals, sse) = css(&w, &ar, &ma, mean, condition);
+
+    let n = residuals.len();
+    let k = spec.n_params();
+    let log_lik = gaussian_log_lik(sse, n);
+    let sigma = (sse / (n - (k - 1)) as f64).sqrt();
+
+    // Forecast the differenced series, then integrate back up.
+    let mut history = w.clone();
+    let mut errors = vec![0.0; w.len() - n];
+    errors.extend(&residuals);
+    for _ in 0..horizon {
+        let t = history.len();
+        let ar_part: f64 = ar
+            .iter()
+            .enumerate()
+            .map(|(i, a)| a * (history[t - 1 - i] - mean))
+            .sum();
+        let ma_part: f64 = ma
+            .iter()
+            .enumerate()
+            .map(|(j, m)| m * errors.get(t - 1 - j).copied().unwrap_or(0.0))
+            .sum();
+        history.push(mean + ar_part + ma_part);
+    }
+    let mut path = history.split_off(w.len());
+    for level in (0..d).rev() {
+        let mut last = *difference(y, level).last().unwrap_or(&0.0);
+        for v in path.iter_mut() {
+            last += *v;
+            *v = last;
+        }
+    }
+
+    let psi = psi_weights(&ar, &ma, d, horizon);
+    let years = series.years();
+    let step = years[1] - years[0];
+    let last_year = years[years.len() - 1];
+    let mut variance_sum = 0.0;
+    let forecast = path
+        .iter()
+        .zip(&psi)
+        .enumerate()
+        .map(|(h, (&point, psi))| {
+            variance_sum += psi * psi;
+            ForecastPoint::new(
+                last_year + step * (h + 1) as f64,
+                point,
+                sigma * variance_sum.sqrt(),
+                None,
+            )
+        })
+        .collect();
+
+    Ok(ArimaFit {
+        p,
+        d,
+        q,
+        ar,
+        ma,
+        mean: spec.has_mean().then_some(mean),
+        sse,
+        sigma,
+        log_lik,
+        aic: aic(log_lik, k),
+        aicc: aicc(log_lik, k, n),
+        bic: bic(log_lik, k, n),
+        residuals,
+        n,
+        forecast,
+    })
+}
+
+/// `d`-th differences of `values`.
+fn difference(values: &[f64], d: usize) -> Vec<f64> {
+    (0..d).fold(values.to_vec(), |v, _| {
+        v.windows(2).map(|w| w[1] - w[0]).collect()
+    })
+}
+
+/// Conditional residuals of an ARMA model with mean `mean`, conditioning
+/// on the first `condition` observations (at least `ar.len()`) and zero
+/// earlier errors. Returns the residuals after that point and their sum
+/// of squares.
+fn css(w: &[f64], ar: &[f64], ma: &[f64], mean: f64, condition: usize) -> (Vec<f64>, f64) {
+    let mut errors = vec![0.0; w.len()];
+    for t in condition..w.len() {
+        let mut e = w[t] - mean;
+        for (i, a) in ar.iter().enumerate() {
+            e -= a * (w[t - 1 - i] - mean);
+        }
+        for (j, m) in ma.iter().enumerate() {
+            if t > j {
+                e -= m * errors[t - 1 - j];
+            }
+        }
+        errors[t] = e;
+    }
+    let residuals = errors.split_off(condition);
+    let sse = residuals.iter().map(|e| e * e).sum();
+    (residuals, sse)
+}
+
+/// Map unconstrained values to the coefficients of a stationary AR
+/// polynomial: `tanh` gives partial autocorrelations in (-1, 1), and the
+/// Durbin–Levinson recursion turns them into coefficients.
+fn from_partials(u: &[f64]) -> Vec<f64> {
+    let mut coef: Vec<f64> = Vec::with_capacity(u.len());
+    for (k, x) in u.iter().enumerate() {
+        let r = x.tanh();
+        let previous = coef.clone();
+        for j in 0..k {
+            coef[j] = previous[j] - r * previous[k - 1 - j];
+        }
+        coef.push(r);
+    }
+    coef
+}
+
+/// First `horizon` MA(∞) weights of the ARIMA model on the original
+/// scale, with the differencing folded into the AR polynomial.
+fn psi_weights(ar: &[f64], ma: &[f64], d: usize, horizon: usize) -> Vec<f64> {
+    // phi*(B) = phi(B) (1 - B)^d, stored as 1 - sum phi*_i B^i.
+    let mut poly = vec![1.0];
+    poly.extend(ar.iter().map(|a| -a));
+    for _ in 0..d {
+        let mut next = poly.clone();
+        next.push(0.0);
+        for i in 1..next.len() {
+            next[i] -= poly[i - 1];
+        }
+        poly = next;
+    }
+    let full_ar: Vec<f64> = poly[1..].iter().map(|c| -c).collect();
+
+    let mut psi = vec![1.0];
+    for j in 1..horizon {
+        let mut v = ma.get(j - 1).copied().unwrap_or(0.0);
+        for (i, a) in full_ar.iter().enumerate().take(j) {
+            v += a * psi[j - 1 - i];
+        }
+        psi.push(v);
+    }
+    psi
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dist;
+    use crate::testing::{annual, assert_close, LH};
+
+    /// AR(1) with coefficient 0.6 driven by a fixed pseudo-random sequence.
+    fn ar1(n: usize) -> YearSeries {
+        let mut state: u64 = 42;
+        let mut noise = || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+        };
+        let mut values = vec![0.0];
+        for t in 1..n {
+            values.push(0.6 * values[t - 1] + noise());
+        }
+        let years: Vec<f64> = (0..n).map(|t| 1990.0 + t as f64).collect();
+        YearSeries::new(&years, &values)
+    }
+
+    fn scaled(series: &YearSeries, factor: f64) -> YearSeries {
+        let values: Vec<f64> = series.values().iter().map(|v| v * factor).collect();
+        YearSeries::new(series.years(), &values)
+    }
+
+    #[test]
+    fn auto_order_does_not_depend_on_units() {
+        let series = ar1(40);
+        let base = auto_arima(&series, 3, 3, 1).unwrap();
+        for factor in [100.0, 1e4, 1e-3] {
+            let fit = auto_arima(&scaled(&series, factor), 3, 3, 1).unwrap();
+            assert_eq!((fit.p, fit.d, fit.q), (base.p, base.d, base.q), "×{factor}");
+            assert!((fit.aicc - base.aicc - 2.0 * fit.n as f64 * factor.ln()).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn candidates_share_one_sample() {
+        let series = ar1(30);
+        let fit = auto_arima(&series, 2, 1, 1).unwrap();
+        assert_eq!(fit.n + fit.d + 2, series.len());
+    }
+
+    #[test]
+    fn ar1_css_is_least_squares() {
+        // CSS for AR(1) with a mean is the regression of y_t on y_{t-1};
+        // references from that regression on R's `lh`.
+        let fit = arima(&annual(&LH), 1, 0, 0, 3).unwrap();
+        let (phi, mean) = (0.585_986_971_670_959, 2.415_057_265_176_187);
+        assert_close(fit.ar[0], phi, 1e-5);
+        assert_close(fit.mean.unwrap(), mean, 1e-5);
+        assert_close(fit.sse, 9.477_327_223_148_008, 1e-8);
+        assert_close(fit.sigma, 0.458_919_678_838_804_3, 1e-6);
+        assert_eq!(fit.n, 47);
+
+        // Forecasts decay toward the mean; psi weights are phi^j.
+        let points = [
+            2.699_227_389_789_426,
+            2.581_577_255_937_658,
+            2.512_635_810_285_177,
+        ];
+        let ses = [
+            0.458_919_678_838_804_3,
+            0.531_907_652_232_487_4,
+            0.554_759_878_364_589_2,
+        ];
+        let z = dist::normal_quantile(0.975);
+        for ((f, point), se) in fit.forecast.iter().zip(points).zip(ses) {
+            assert_close(f.point, point, 1e-5);
+            assert_close(f.hi95 - f.point, z * se, 1e-5);
+        }
+    }
+
+    #[test]
+    fn random_walk_with_drift() {
+        let fit = arima(&annual(&LH), 0, 1, 0, 3).unwrap();
+        let n = LH.len();
+        let drift = (LH[n - 1] - LH[0]) / (n - 1) as f64;
+        let sse: f64 = LH.windows(2).map(|w| (w[1] - w[0] - drift).powi(2)).sum();
+        let sigma = (sse / (n - 2) as f64).sqrt();
+        assert_close(fit.mean.unwrap(), drift, 1e-6);
+        assert_close(fit.sigma, sigma, 1e-6);
+        let z = dist::normal_quantile(0.9);
+        for (h, f) in fit.forecast.iter().enumerate() {
+            let steps = (h + 1) as f64;
+            assert_close(f.point, LH[n - 1] + steps * drift, 1e-6);
+            assert_close(f.hi80 - f.point, z * sigma * steps.sqrt(), 1e-6);
+        }
+    }
+
+    #[test]
+    fn estimates_stay_stationary_and_invertible() {
+        let fit = arima(&annual(&LH), 2, 0, 1, 1).unwrap();
+        let (a1, a2) = (fit.ar[0], fit.ar[1]);
+        assert!(a2.abs() < 1.0 && a1 + a2 < 1.0 && a2 - a1 < 1.0);
+        assert!(fit.ma[0].abs() < 1.0);
+    }
+}
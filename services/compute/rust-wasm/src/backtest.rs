@@ -0,0 +1,253 @@
+//! Rolling-origin evaluation of forecast methods.
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::error::{require_range, require_regular, ComputeError};
+use crate::ets::{self, EtsModel};
+use crate::forecast::{self, ForecastPoint};
+use crate::series::YearSeries;
+use crate::{arima, stats};
+
+/// Order limits for `ForecastMethod::AutoArima`, matching `/forecast`.
+const AUTO_MAX_P: usize = 3;
+const AUTO_MAX_Q: usize = 3;
+
+/// Forecast method to evaluate.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ForecastMethod {
+    /// Repeat the last observation; the baseline every method should beat.
+    #[default]
+    Naive = 0,
+    /// Year-aware linear trend, as in `forecast`.
+    Linear = 1,
+    SimpleSmoothing = 2,
+    Holt = 3,
+    DampedTrend = 4,
+    AutoArima = 5,
+}
+
+impl ForecastMethod {
+    /// Forecast `horizon` steps from `series`.
+    pub fn forecast(
+        self,
+        series: &YearSeries,
+        horizon: usize,
+    ) -> Result<Vec<ForecastPoint>, ComputeError> {
+        match self {
+            ForecastMethod::Naive => naive(series, horizon),
+            ForecastMethod::Linear => forecast::linear_forecast(series, horizon),
+            ForecastMethod::SimpleSmoothing => {
+                Ok(ets::ets(series, EtsModel::Simple, horizon)?.forecast)
+            }
+            ForecastMethod::Holt => Ok(ets::ets(series, EtsModel::Holt, horizon)?.forecast),
+            ForecastMethod::DampedTrend => {
+                Ok(ets::ets(series, EtsModel::Damped, horizon)?.forecast)
+            }
+            ForecastMethod::AutoArima => {
+                Ok(arima::auto_arima(series, AUTO_MAX_P, AUTO_MAX_Q, horizon)?.forecast)
+            }
+        }
+    }
+}
+
+/// Forecast accuracy at one horizon, pooled over forecast origins.
+#[derive(Debug, Clone, Serialize)]
+pub struct HorizonAccuracy {
+    /// Steps ahead of the origin.
+    pub horizon: usize,
+    /// Forecasts with an actual value to compare against.
+    pub n: usize,
+    pub mae: f64,
+    pub rmse: f64,
+    /// Mean absolute percentage error, in percent. `None` if every actual
+    /// value is zero.
+    pub mape: Option<f64>,
+    /// Symmetric MAPE, in percent, bounded by 200.
+    pub smape: Option<f64>,
+    /// Mean absolute scaled error: errors over the in-sample mean
+    /// absolute one-step change of each training window. Below 1 beats
+    /// the in-sample one-step naive forecast; only at `horizon` 1 does
+    /// that mean beating the naive forecast, whose own MASE grows with
+    /// the horizon. `None` if every training window is flat.
+    pub mase: Option<f64>,
+}
+
+/// Result of a rolling-origin backtest.
+#[derive(Debug, Clone, Serialize)]
+pub struct Backtest {
+    /// Forecast origins evaluated.
+    pub origins: usize,
+    /// Accuracy per horizon from 1 to `max_horizon`; horizons no origin
+    /// reaches are omitted.
+    pub horizons: Vec<HorizonAccuracy>,
+}
+
+/// Evaluate `method` by time-series cross-validation on an evenly spaced
+/// series.
+///
+/// The first forecast origin trains on the first `min_train` points; each
+/// later origin adds one more. From every origin the method forecasts up
+/// to `max_horizon` steps, and each forecast that lands on an observed
+/// point is scored against the value observed in its year. Errors from the
+/// method, such as a training window too short for it, are passed on, and
+/// a forecast whose year is not the series' next year at that step is
+/// rejected as `Irregular` rather than scored against the wrong value.
+pub fn backtest<F>(
+    series: &YearSeries,
+    method: F,
+    min_train: usize,
+    max_horizon: usize,
+) -> Result<Backtest, ComputeError>
+where
+    F: Fn(&YearSeries, usize) -> Result<Vec<ForecastPoint>, ComputeError>,
+{
+    require_regular("years", series.years())?;
+    let n = series.len();
+    require_range("min_train", min_train as f64, 2.0, n as f64 - 1.0)?;
+    require_range("max_horizon", max_horizon as f64, 1.0, f64::INFINITY)?;
+    let (years, values) = (series.years(), series.values());
+
+    // (absolute, squared, percentage, symmetric, scaled) errors per horizon.
+    let mut errors: Vec<Vec<[Option<f64>; 5]>> = vec![Vec::new(); max_horizon];
+    for origin in min_train..n {
+        let train = YearSeries::new(&years[..origin], &values[..origin]);
+        let horizon = max_horizon.min(n - origin);
+        let forecasts = method(&train, horizon)?;
+        let scale = stats::mean(
+            &values[..origin]
+                .windows(2)
+                .map(|w| (w[1] - w[0]).abs())
+                .collect::<Vec<f64>>(),
+        )
+        .ok()
+        .filter(|&s| s > 0.0);
+        for (h, f) in forecasts.iter().take(horizon).enumerate() {
+            let year = years[origin + h];
+            if (f.year - year).abs() > 1e-9 * year.abs().max(1.0) {
+                return Err(ComputeError::Irregular { arg: "forecast" });
+            }
+            let actual = values[origin + h];
+            let e = actual - f.point;
+            let denominator = actual.abs() + f.point.abs();
+            errors[h].push([
+                Some(e.abs()),
+                Some(e * e),
+                (actual != 0.0).then(|| 100.0 * (e / actual).abs()),
+                (denominator > 0.0).then(|| 200.0 * e.abs() / denominator),
+                scale.map(|s| e.abs() / s),
+            ]);
+        }
+    }
+
+    let mean_of = |rows: &[[Option<f64>; 5]], i: usize| -> Option<f64> {
+        let kept: Vec<f64> = rows.iter().filter_map(|r| r[i]).collect();
+        stats::mean(&kept).ok()
+    };
+    let horizons = errors
+        .iter()
+        .enumerate()
+        .filter(|(_, rows)| !rows.is_empty())
+        .map(|(h, rows)| HorizonAccuracy {
+            horizon: h + 1,
+            n: rows.len(),
+            mae: mean_of(rows, 0).unwrap_or(f64::NAN),
+            rmse: mean_of(rows, 1).map_or(f64::NAN, f64::sqrt),
+            mape: mean_of(rows, 2),
+            smape: mean_of(rows, 3),
+            mase: mean_of(rows, 4),
+        })
+        .collect();
+    Ok(Backtest {
+        origins: n - min_train,
+        horizons,
+    })
+}
+
+/// Flat forecast at the last observation, with random-walk intervals
+/// from the in-sample one-step changes.
+fn naive(series: &YearSeries, horizon: usize) -> Result<Vec<ForecastPoint>, ComputeError> {
+    require_range("horizon", horizon as f64, 1.0, f64::INFINITY)?;
+    let (years, values) = (series.years(), series.values());
+    let changes: Vec<f64> = values.windows(2).map(|w| w[1] - w[0]).collect();
+    let sigma = (changes.iter().map(|c| c * c).sum::<f64>() / changes.len() as f64).sqrt();
+    let step = years.get(1).map_or(1.0, |y| y - years[0]);
+    let (&last_year, &last) = years
+        .last()
+        .zip(values.last())
+        .ok_or(ComputeError::Empty { arg: "values" })?;
+    Ok((1..=horizon)
+        .map(|h| {
+            ForecastPoint::new(
+                last_year + step * h as f64,
+                last,
+                sigma * (h as f64).sqrt(),
+                None,
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    /// A straight line observed every five years.
+    fn five_yearly() -> YearSeries {
+        let years: Vec<f64> = (0..8).map(|i| 1990.0 + 5.0 * i as f64).collect();
+        let values: Vec<f64> = years.iter().map(|y| 2.0 * (y - 1990.0)).collect();
+        YearSeries::new(&years, &values)
+    }
+
+    fn run(method: ForecastMethod) -> Backtest {
+        backtest(&five_yearly(), |s, h| method.forecast(s, h), 4, 2).unwrap()
+    }
+
+    #[test]
+    fn linear_is_exact_on_a_line_at_any_spacing() {
+        let result = run(ForecastMethod::Linear);
+        assert_eq!(result.origins, 4);
+        assert_eq!(result.horizons.len(), 2);
+        assert_eq!((result.horizons[0].n, result.horizons[1].n), (4, 3));
+        for h in &result.horizons {
+            assert_close(h.mae, 0.0, 1e-9);
+            assert_close(h.rmse, 0.0, 1e-9);
+            assert_close(h.mase.unwrap(), 0.0, 1e-9);
+        }
+    }
+
+    #[test]
+    fn naive_errors_grow_with_horizon() {
+        // Each step adds 10, so the h-step naive error is 10h and MASE is h.
+        let result = run(ForecastMethod::Naive);
+        for (h, accuracy) in result.horizons.iter().enumerate() {
+            let h = (h + 1) as f64;
+            assert_close(accuracy.mae, 10.0 * h, 1e-9);
+            assert_close(accuracy.rmse, 10.0 * h, 1e-9);
+            assert_close(accuracy.mase.unwrap(), h, 1e-9);
+        }
+        // Actuals of 40..70 against origins 30..60.
+        let smape = [40.0, 50.0, 60.0, 70.0]
+            .iter()
+            .map(|a| 200.0 * 10.0 / (2.0 * a - 10.0))
+            .sum::<f64>()
+            / 4.0;
+        assert_close(result.horizons[0].smape.unwrap(), smape, 1e-9);
+    }
+
+    #[test]
+    fn rejects_forecasts_off_the_series_years() {
+        let annual = |s: &YearSeries, h: usize| {
+            let last = s.years()[s.len() - 1];
+            Ok((1..=h)
+                .map(|i| ForecastPoint::new(last + i as f64, 0.0, 1.0, None))
+                .collect())
+        };
+        assert_eq!(
+            backtest(&five_yearly(), annual, 4, 2).unwrap_err(),
+            ComputeError::Irregular { arg: "forecast" }
+        );
+    }
+}
@@ -0,0 +1,346 @@
+//! Growth curves and forecasts that respect natural bounds, for rates and
+//! percentages that cannot leave e.g. [0, 100].
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::error::{require_len, require_range, ComputeError};
+use crate::forecast::{self, ForecastPoint};
+use crate::linalg::{self, Matrix};
+use crate::regression;
+use crate::series::YearSeries;
+
+/// Starting values come from the linearized curve, with shares clamped
+/// this far inside (0, 1) so points on a bound stay finite.
+const EDGE: f64 = 1e-3;
+
+/// S-shaped curve rising (or falling) between two asymptotes.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GrowthCurve {
+    /// Symmetric about its midpoint.
+    #[default]
+    Logistic = 0,
+    /// Fast early change that approaches the upper asymptote slowly;
+    /// typical of coverage indicators closing the last few percent.
+    Gompertz = 1,
+}
+
+impl GrowthCurve {
+    /// Share of the way from `lo` to `hi` at time `t`, and its derivative
+    /// with respect to `z = rate * (t - midpoint)`.
+    fn share(self, z: f64) -> (f64, f64) {
+        match self {
+            GrowthCurve::Logistic => {
+                let s = 1.0 / (1.0 + (-z).exp());
+                (s, s * (1.0 - s))
+            }
+            GrowthCurve::Gompertz => {
+                let u = (-z).exp();
+                let s = (-u).exp();
+                (s, s * u)
+            }
+        }
+    }
+
+    /// Inverse of `share`, used to linearize the data.
+    fn linearize(self, share: f64) -> f64 {
+        let share = share.clamp(EDGE, 1.0 - EDGE);
+        match self {
+            GrowthCurve::Logistic => (share / (1.0 - share)).ln(),
+            GrowthCurve::Gompertz => -(-share.ln()).ln(),
+        }
+    }
+}
+
+/// A growth curve fitted between fixed bounds, with forecasts.
+#[derive(Debug, Clone, Serialize)]
+pub struct GrowthCurveFit {
+    /// Steepness per year; negative for a falling curve.
+    pub rate: f64,
+    /// Year at which the logistic is halfway between the bounds, or the
+    /// Gompertz curve is at 1/e of the way.
+    pub midpoint: f64,
+    pub lo: f64,
+    pub hi: f64,
+    pub sse: f64,
+    pub sigma: f64,
+    pub r_squared: f64,
+    pub n: usize,
+    /// Intervals combine residual noise with parameter uncertainty and
+    /// are clipped to `[lo, hi]`.
+    pub forecast: Vec<ForecastPoint>,
+}
+
+/// Fit `curve` between the asymptotes `lo` and `hi` by nonlinear least
+/// squares and forecast `horizon` steps past the last observation,
+/// stepping by the series' spacing as `forecast::linear_forecast` does.
+///
+/// The bounds are taken as given, not estimated, so they should be the
+/// natural limits of the indicator (0 and 100 for a percentage) or a
+/// declared ceiling. Values must lie within them.
+pub fn growth_curve(
+    series: &YearSeries,
+    curve: GrowthCurve,
+    lo: f64,
+    hi: f64,
+    horizon: usize,
+) -> Result<GrowthCurveFit, ComputeError> {
+    check_bounds(series, lo, hi, false)?;
+    require_range("horizon", horizon as f64, 1.0, f64::INFINITY)?;
+    require_len("values", series.values(), 3)?;
+    let (years, values) = (series.years(), series.values());
+    let n = values.len();
+    let span = hi - lo;
+
+    // Start from a straight-line fit on the linearized scale.
+    let z: Vec<f64> = values
+        .iter()
+        .map(|v| curve.linearize((v - lo) / span))
+        .collect();
+    let line = regression::linear_fit(years, &z)?;
+    let mut rate = line.slope;
+    let mut midpoint = if line.slope == 0.0 {
+        years.iter().sum::<f64>() / n as f64
+    } else {
+        -line.intercept / line.slope
+    };
+
+    let model = |rate: f64, midpoint: f64, t: f64| -> (f64, [f64; 2]) {
+        let (s, ds) = curve.share(rate * (t - midpoint));
+        (
+            lo + span * s,
+            [span * ds * (t - midpoint), -span * ds * rate],
+        )
+    };
+    let sse_at = |rate: f64, midpoint: f64| -> f64 {
+        years
+            .iter()
+            .zip(values)
+            .map(|(&t, v)| (v - model(rate, midpoint, t).0).powi(2))
+            .sum()
+    };
+
+    // Levenberg–Marquardt: damped Gauss–Newton steps, solved as an
+    // augmented least-squares problem.
+    let mut sse = sse_at(rate, midpoint);
+    let mut damping = 1e-3;
+    for _ in 0..200 {
+        let mut jacobian = Matrix::zeros(n + 2, 2);
+        let mut residuals = vec![0.0; n + 2];
+        for (i, (&t, v)) in years.iter().zip(values).enumerate() {
+            let (fitted, grad) = model(rate, midpoint, t);
+            jacobian[(i, 0)] = grad[0];
+            jacobian[(i, 1)] = grad[1];
+            residuals[i] = v - fitted;
+        }
+        let scale = (0..2)
+            .map(|j| {
+                (0..n)
+                    .map(|i| jacobian[(i, j)].powi(2))
+                    .sum::<f64>()
+                    .max(1e-12)
+            })
+            .collect::<Vec<f64>>();
+        jacobian[(n, 0)] = (damping * scale[0]).sqrt();
+        jacobian[(n + 1, 1)] = (damping * scale[1]).sqrt();
+        let Some(step) = linalg::lstsq(&jacobian, &residuals) else {
+            break;
+        };
+        let (next_rate, next_midpoint) = (rate + step.coef[0], midpoint + step.coef[1]);
+        let next_sse = sse_at(next_rate, next_midpoint);
+        if next_sse < sse {
+            let converged = sse - next_sse <= 1e-12 * sse.max(f64::MIN_POSITIVE);
+            (rate, midpoint, sse) = (next_rate, next_midpoint, next_sse);
+            damping = (damping / 10.0).max(1e-12);
+            if converged {
+                break;
+            }
+        } else {
+            damping *= 10.0;
+            if damping > 1e12 {
+                break;
+            }
+        }
+    }
+
+    // Prediction variance: residual noise plus g' (J'J)^-1 g sigma^2,
+    // where g is the gradient of the curve at the forecast year.
+    let df = (n - 2) as f64;
+    let sigma2 = sse / df;
+    let mut jacobian = Matrix::zeros(n, 2);
+    for (i, &t) in years.iter().enumerate() {
+        let grad = model(rate, midpoint, t).1;
+        jacobian[(i, 0)] = grad[0];
+        jacobian[(i, 1)] = grad[1];
+    }
+    let covariance = jacobian.gram().invert();
+    let step = forecast::step(years);
+    let last = years[n - 1];
+    let forecast = (1..=horizon)
+        .map(|h| {
+            let year = last + step * h as f64;
+            let (point, grad) = model(rate, midpoint, year);
+            let parameter_var = covariance.as_ref().map_or(0.0, |c| {
+                let cg = c.mul_vec(&grad);
+                grad.iter().zip(&cg).map(|(g, c)| g * c).sum::<f64>()
+            });
+            let se = (sigma2 * (1.0 + parameter_var)).sqrt();
+            clip(ForecastPoint::new(year, point, se, Some(df)), lo, hi)
+        })
+        .collect();
+
+    let mean = values.iter().sum::<f64>() / n as f64;
+    let tss: f64 = values.iter().map(|v| (v - mean).powi(2)).sum();
+    Ok(GrowthCurveFit {
+        rate,
+        midpoint,
+        lo,
+        hi,
+        sse,
+        sigma: sigma2.sqrt(),
+        r_squared: if tss == 0.0 {
+            f64::NAN
+        } else {
+            1.0 - sse / tss
+        },
+        n,
+        forecast,
+    })
+}
+
+/// Forecast a linear trend on the logit scale between `lo` and `hi`,
+/// mapping the point and interval bounds back so every number stays
+/// strictly inside the bounds.
+///
+/// The back-transformed point is the median forecast. Values must lie
+/// strictly between the bounds.
+pub fn logit_forecast(
+    series: &YearSeries,
+    lo: f64,
+    hi: f64,
+    horizon: usize,
+) -> Result<Vec<ForecastPoint>, ComputeError> {
+    check_bounds(series, lo, hi, true)?;
+    let span = hi - lo;
+    let logits: Vec<f64> = series
+        .values()
+        .iter()
+        .map(|v| {
+            let share = (v - lo) / span;
+            (share / (1.0 - share)).ln()
+        })
+        .collect();
+    let transformed = YearSeries::new(series.years(), &logits);
+    let back = |z: f64| lo + span / (1.0 + (-z).exp());
+    Ok(forecast::linear_forecast(&transformed, horizon)?
+        .into_iter()
+        .map(|p| ForecastPoint {
+            year: p.year,
+            point: back(p.point),
+            lo80: back(p.lo80),
+            hi80: back(p.hi80),
+            lo95: back(p.lo95),
+            hi95: back(p.hi95),
+        })
+        .collect())
+}
+
+/// Check `lo < hi` and that every value lies within the bounds, or
+/// strictly inside them if `strict`.
+fn check_bounds(series: &YearSeries, lo: f64, hi: f64, strict: bool) -> Result<(), ComputeError> {
+    if lo.partial_cmp(&hi) != Some(std::cmp::Ordering::Less) {
+        return Err(ComputeError::OutOfRange {
+            arg: "hi",
+            value: hi,
+            min: lo,
+            max: f64::INFINITY,
+        });
+    }
+    for &v in series.values() {
+        let inside = if strict {
+            v > lo && v < hi
+        } else {
+            v >= lo && v <= hi
+        };
+        if !inside {
+            return Err(ComputeError::OutOfRange {
+                arg: "values",
+                value: v,
+                min: lo,
+                max: hi,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Clip a forecast's point and intervals to `[lo, hi]`.
+fn clip(p: ForecastPoint, lo: f64, hi: f64) -> ForecastPoint {
+    let c = |v: f64| v.clamp(lo, hi);
+    ForecastPoint {
+        year: p.year,
+        point: c(p.point),
+        lo80: c(p.lo80),
+        hi80: c(p.hi80),
+        lo95: c(p.lo95),
+        hi95: c(p.hi95),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    fn logistic_series(years: &[f64]) -> YearSeries {
+        let values: Vec<f64> = years
+            .iter()
+            .map(|t| 100.0 / (1.0 + (-0.3 * (t - 2005.0)).exp()))
+            .collect();
+        YearSeries::new(years, &values)
+    }
+
+    #[test]
+    fn logistic_recovers_exact_curve() {
+        let years: Vec<f64> = (1995..=2015).map(f64::from).collect();
+        let fit = growth_curve(
+            &logistic_series(&years),
+            GrowthCurve::Logistic,
+            0.0,
+            100.0,
+            3,
+        )
+        .unwrap();
+        assert_close(fit.rate, 0.3, 1e-6);
+        assert_close(fit.midpoint, 2005.0, 1e-9);
+        let expected = 100.0 / (1.0 + (-0.3f64 * 11.0).exp());
+        assert_close(fit.forecast[0].year, 2016.0, 1e-9);
+        assert_close(fit.forecast[0].point, expected, 1e-6);
+    }
+
+    #[test]
+    fn forecasts_step_by_series_spacing() {
+        let years: Vec<f64> = (0..12).map(|q| 2003.0 + 0.25 * q as f64).collect();
+        let series = logistic_series(&years);
+        let fit = growth_curve(&series, GrowthCurve::Gompertz, 0.0, 100.0, 2).unwrap();
+        assert_close(fit.forecast[0].year, 2006.0, 1e-9);
+        assert_close(fit.forecast[1].year, 2006.25, 1e-9);
+        let logit = logit_forecast(&series, 0.0, 100.0, 1).unwrap();
+        assert_close(logit[0].year, 2006.0, 1e-9);
+    }
+
+    #[test]
+    fn intervals_stay_within_bounds() {
+        let years: Vec<f64> = (2000..2010).map(f64::from).collect();
+        let values = [91.0, 93.5, 94.0, 96.5, 97.0, 98.5, 98.0, 99.2, 99.0, 99.6];
+        let series = YearSeries::new(&years, &values);
+        let fit = growth_curve(&series, GrowthCurve::Logistic, 0.0, 100.0, 10).unwrap();
+        let logit = logit_forecast(&series, 0.0, 100.0, 10).unwrap();
+        for p in fit.forecast.iter().chain(&logit) {
+            assert!(p.lo95 >= 0.0 && p.hi95 <= 100.0);
+            assert!(p.lo95 <= p.point && p.point <= p.hi95);
+        }
+        assert!(logit.iter().all(|p| p.hi95 < 100.0));
+    }
+}
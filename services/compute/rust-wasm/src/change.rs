@@ -0,0 +1,356 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::error::{require_len, require_level, require_range, ComputeError};
+use crate::series::YearSeries;
+use crate::{dist, regression, stats};
+
+/// Ways of expressing the change between the first and last observation.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeMeasure {
+    /// `last - first`, in the indicator's own unit.
+    Absolute = 0,
+    /// `last - first` for indicators already measured in percent. Same
+    /// number as `Absolute`, reported in percentage points.
+    PercentagePoint = 1,
+    /// `(last - first) / |first| * 100`.
+    Percent = 2,
+    /// `(last - first) / mean(|first|, |last|) * 100`; symmetric in the
+    /// endpoints and bounded to ±200.
+    SymmetricPercent = 3,
+    /// `ln(last / first)`; additive across consecutive periods.
+    LogRatio = 4,
+    /// Compound annual growth rate, in percent per year.
+    Cagr = 5,
+}
+
+/// Pick the change measure that reads naturally for an indicator unit:
+/// percentage points for rates already in percent, CAGR for monetary
+/// amounts, and relative percent change for everything else.
+pub fn default_measure(unit: &str) -> ChangeMeasure {
+    let unit = unit.trim().to_ascii_lowercase();
+    if unit.contains('%') || unit.contains("percent") {
+        ChangeMeasure::PercentagePoint
+    } else if ["usd", "inr", "eur", "$", "₹", "€"]
+        .iter()
+        .any(|c| unit.contains(c))
+    {
+        ChangeMeasure::Cagr
+    } else {
+        ChangeMeasure::Percent
+    }
+}
+
+/// Change between the first and last year of `series` under `measure`.
+pub fn change(series: &YearSeries, measure: ChangeMeasure) -> Result<f64, ComputeError> {
+    let values = series.values();
+    require_len("values", values, 2)?;
+    let years = series.years();
+    let first = values[0];
+    let last = values[values.len() - 1];
+
+    match measure {
+        ChangeMeasure::Absolute | ChangeMeasure::PercentagePoint => Ok(last - first),
+        ChangeMeasure::Percent => percent_change(first, last),
+        ChangeMeasure::SymmetricPercent => symmetric_percent_change(first, last),
+        ChangeMeasure::LogRatio => log_ratio(first, last),
+        ChangeMeasure::Cagr => cagr(first, last, years[years.len() - 1] - years[0]),
+    }
+}
+
+/// Percent change between the first and last value, relative to the
+/// magnitude of the first.
+pub fn pct_change(values: &[f64]) -> Result<f64, ComputeError> {
+    require_len("values", values, 2)?;
+    percent_change(values[0], values[values.len() - 1])
+}
+
+/// `(last - first) / |first| * 100`.
+pub fn percent_change(first: f64, last: f64) -> Result<f64, ComputeError> {
+    if first == 0.0 {
+        return Err(ComputeError::ZeroBase { arg: "values" });
+    }
+    Ok(((last - first) / first.abs()) * 100.0)
+}
+
+/// `(last - first) / ((|first| + |last|) / 2) * 100`.
+pub fn symmetric_percent_change(first: f64, last: f64) -> Result<f64, ComputeError> {
+    let base = (first.abs() + last.abs()) / 2.0;
+    if base == 0.0 {
+        return Err(ComputeError::ZeroBase { arg: "values" });
+    }
+    Ok((last - first) / base * 100.0)
+}
+
+/// Natural log of `last / first`. Both must be positive.
+pub fn log_ratio(first: f64, last: f64) -> Result<f64, ComputeError> {
+    if first <= 0.0 || last <= 0.0 {
+        return Err(ComputeError::NonPositive { arg: "values" });
+    }
+    Ok((last / first).ln())
+}
+
+/// Compound annual growth rate in percent over `span` years.
+pub fn cagr(first: f64, last: f64, span: f64) -> Result<f64, ComputeError> {
+    if first <= 0.0 || last <= 0.0 {
+        return Err(ComputeError::NonPositive { arg: "values" });
+    }
+    if span <= 0.0 {
+        return Err(ComputeError::ZeroVariance { arg: "years" });
+    }
+    Ok(((last / first).powf(1.0 / span) - 1.0) * 100.0)
+}
+
+/// Change over a period estimated from smoothed endpoints rather than the
+/// raw first and last observations, with a confidence interval.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangeEstimate {
+    /// Year the estimated start level refers to.
+    pub start_year: f64,
+    /// Year the estimated end level refers to.
+    pub end_year: f64,
+    /// Estimated level at `start_year`.
+    pub start: f64,
+    /// Estimated level at `end_year`.
+    pub end: f64,
+    /// `end - start`.
+    pub change: f64,
+    pub change_lo: f64,
+    pub change_hi: f64,
+    /// `change` relative to `|start|`, in percent. `None` if `start` is 0.
+    pub pct_change: Option<f64>,
+    /// Interval for `pct_change`, treating `start` as fixed.
+    pub pct_lo: Option<f64>,
+    pub pct_hi: Option<f64>,
+    /// Confidence level of the intervals, e.g. 0.95.
+    pub level: f64,
+}
+
+impl ChangeEstimate {
+    fn new(
+        start_year: f64,
+        end_year: f64,
+        start: f64,
+        end: f64,
+        half_width: f64,
+        level: f64,
+    ) -> Self {
+        let change = end - start;
+        let pct = |v: f64| (start != 0.0).then(|| v / start.abs() * 100.0);
+        ChangeEstimate {
+            start_year,
+            end_year,
+            start,
+            end,
+            change,
+            change_lo: change - half_width,
+            change_hi: change + half_width,
+            pct_change: pct(change),
+            pct_lo: pct(change - half_width),
+            pct_hi: pct(change + half_width),
+            level,
+        }
+    }
+}
+
+/// Change implied by the fitted linear trend between the first and last
+/// year. A noisy endpoint moves this only through its share of the fit.
+///
+/// The interval is `slope ± t * se(slope)` scaled by the year span, with
+/// n - 2 degrees of freedom, so at least 3 points are needed.
+pub fn fitted_change(series: &YearSeries, level: f64) -> Result<ChangeEstimate, ComputeError> {
+    require_level(level)?;
+    require_len("values", series.values(), 3)?;
+    let fit = regression::linear_fit(series.years(), series.values())?;
+
+    let years = series.years();
+    let (y0, y1) = (years[0], years[years.len() - 1]);
+    let t = dist::student_t_quantile(0.5 + level / 2.0, fit.n as f64 - 2.0);
+    Ok(ChangeEstimate::new(
+        y0,
+        y1,
+        fit.predict(y0),
+        fit.predict(y1),
+        t * fit.slope_std_error * (y1 - y0),
+        level,
+    ))
+}
+
+/// Change between the average of the first `window` and the last `window`
+/// observations. Each average is dated at the mean year of its window.
+///
+/// The interval is a Welch two-sample t interval on the two window means.
+/// Any trend within a window widens it, so it errs on the conservative side.
+pub fn trimmed_change(
+    series: &YearSeries,
+    window: usize,
+    level: f64,
+) -> Result<ChangeEstimate, ComputeError> {
+    require_level(level)?;
+    require_range("window", window as f64, 2.0, (series.len() / 2) as f64)?;
+    let n = series.len();
+    let (years, values) = (series.years(), series.values());
+    let head = &values[..window];
+    let tail = &values[n - window..];
+
+    let start = stats::mean(head)?;
+    let end = stats::mean(tail)?;
+    let k = window as f64;
+    let (v0, v1) = (stats::variance(head)? / k, stats::variance(tail)? / k);
+    let se = (v0 + v1).sqrt();
+    let half_width = if se == 0.0 {
+        0.0
+    } else {
+        let df = (v0 + v1).powi(2) / (v0 * v0 / (k - 1.0) + v1 * v1 / (k - 1.0));
+        dist::student_t_quantile(0.5 + level / 2.0, df) * se
+    };
+
+    Ok(ChangeEstimate::new(
+        stats::mean(&years[..window])?,
+        stats::mean(&years[n - window..])?,
+        start,
+        end,
+        half_width,
+        level,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    fn series() -> YearSeries {
+        YearSeries::new(&[2000.0, 2005.0, 2010.0], &[40.0, 55.0, 50.0])
+    }
+
+    #[test]
+    fn absolute_and_percentage_point() {
+        assert_close(
+            change(&series(), ChangeMeasure::Absolute).unwrap(),
+            10.0,
+            1e-12,
+        );
+        assert_close(
+            change(&series(), ChangeMeasure::PercentagePoint).unwrap(),
+            10.0,
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn percent() {
+        assert_close(
+            change(&series(), ChangeMeasure::Percent).unwrap(),
+            25.0,
+            1e-12,
+        );
+        // Relative to the magnitude of a negative base.
+        assert_close(percent_change(-4.0, -2.0).unwrap(), 50.0, 1e-12);
+        assert_eq!(
+            percent_change(0.0, 3.0),
+            Err(ComputeError::ZeroBase { arg: "values" })
+        );
+    }
+
+    #[test]
+    fn symmetric_percent() {
+        let value = change(&series(), ChangeMeasure::SymmetricPercent).unwrap();
+        assert_close(value, 200.0 / 9.0, 1e-12);
+        assert_close(symmetric_percent_change(0.0, 3.0).unwrap(), 200.0, 1e-12);
+        assert_eq!(
+            symmetric_percent_change(0.0, 0.0),
+            Err(ComputeError::ZeroBase { arg: "values" })
+        );
+    }
+
+    #[test]
+    fn log_ratio_measure() {
+        let value = change(&series(), ChangeMeasure::LogRatio).unwrap();
+        assert_close(value, 0.223_143_551_314_209_76, 1e-12);
+        assert_eq!(
+            log_ratio(-1.0, 2.0),
+            Err(ComputeError::NonPositive { arg: "values" })
+        );
+    }
+
+    #[test]
+    fn cagr_uses_the_year_span() {
+        // 25% over ten years, not over the two intervals between points.
+        let value = change(&series(), ChangeMeasure::Cagr).unwrap();
+        assert_close(value, 2.256_518_256_357_292_7, 1e-12);
+        assert_eq!(
+            cagr(1.0, 0.0, 5.0),
+            Err(ComputeError::NonPositive { arg: "values" })
+        );
+        assert_eq!(
+            cagr(1.0, 2.0, 0.0),
+            Err(ComputeError::ZeroVariance { arg: "years" })
+        );
+    }
+
+    #[test]
+    fn percentage_units_default_to_percentage_points() {
+        assert_eq!(default_measure("%"), ChangeMeasure::PercentagePoint);
+        assert_eq!(
+            default_measure(" Percent of population "),
+            ChangeMeasure::PercentagePoint
+        );
+        assert_eq!(default_measure("USD per capita"), ChangeMeasure::Cagr);
+        assert_eq!(default_measure("₹ crore"), ChangeMeasure::Cagr);
+        assert_eq!(
+            default_measure("per 1,000 live births"),
+            ChangeMeasure::Percent
+        );
+    }
+
+    fn noisy() -> YearSeries {
+        YearSeries::new(
+            &[2000.0, 2002.0, 2005.0, 2007.0, 2010.0],
+            &[10.0, 13.0, 15.0, 19.0, 20.0],
+        )
+    }
+
+    #[test]
+    fn fitted_change_interval_uses_the_slope_error() {
+        let est = fitted_change(&noisy(), 0.95).unwrap();
+        assert_eq!((est.start_year, est.end_year), (2000.0, 2010.0));
+        assert_close(est.start, 10.477_707_006_369_427, 1e-12);
+        assert_close(est.end, 20.732_484_076_433_12, 1e-12);
+        assert_close(est.change, 10.254_777_070_063_694, 1e-12);
+        // slope ± qt(0.975, 3) * se(slope), over the ten-year span.
+        assert_close(est.change_lo, 6.133_691_353_630_095, 1e-9);
+        assert_close(est.change_hi, 14.375_862_786_497_293, 1e-9);
+        assert_close(est.pct_change.unwrap(), 97.872_340_425_531_91, 1e-12);
+        assert_close(est.pct_lo.unwrap(), 58.540_397_721_575_99, 1e-9);
+
+        let narrow = fitted_change(&noisy(), 0.9).unwrap();
+        assert_close(
+            narrow.change_hi - narrow.change,
+            3.047_470_878_184_797,
+            1e-9,
+        );
+    }
+
+    #[test]
+    fn trimmed_change_uses_welch_interval() {
+        let est = trimmed_change(&noisy(), 2, 0.95).unwrap();
+        assert_eq!((est.start_year, est.end_year), (2001.0, 2008.5));
+        assert_eq!((est.start, est.end, est.change), (11.5, 19.5, 8.0));
+        // Welch-Satterthwaite gives 1.2195 degrees of freedom here.
+        assert_close(est.change_lo, -5.272_108_889_547_318, 1e-8);
+        assert_close(est.change_hi, 21.272_108_889_547_32, 1e-8);
+        assert!(trimmed_change(&noisy(), 3, 0.95).is_err());
+    }
+
+    #[test]
+    fn level_must_be_strictly_inside_zero_and_one() {
+        for level in [0.0, 1.0, -0.5, f64::NAN] {
+            let err = fitted_change(&noisy(), level).unwrap_err();
+            assert_eq!(err.code(), "OUT_OF_RANGE");
+            assert_eq!(err.arg(), "level");
+            assert!(trimmed_change(&noisy(), 2, level).is_err());
+        }
+    }
+}
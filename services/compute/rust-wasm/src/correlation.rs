@@ -0,0 +1,989 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::error::{require_len, require_level, require_range, require_same_len, ComputeError};
+use crate::linalg::{self, Matrix};
+use crate::multiple::{self, PAdjustMethod};
+use crate::series::YearSeries;
+use crate::{dist, regression};
+
+/// Below this effective sample size a correlation is flagged as unreliable.
+pub const MIN_RELIABLE_N: f64 = 10.0;
+
+/// How far the raw correlation may sit from both the detrended and the
+/// differenced correlation before it is attributed to a shared trend.
+pub const SHARED_TREND_GAP: f64 = 0.5;
+
+/// Correlation coefficient to compute.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CorrelationMethod {
+    /// Linear association of the raw values.
+    #[default]
+    Pearson = 0,
+    /// Pearson correlation of the ranks; any monotone association.
+    Spearman = 1,
+    /// Kendall's tau-b: concordant minus discordant pairs, tie-corrected.
+    Kendall = 2,
+}
+
+/// Significance test of a correlation coefficient.
+#[derive(Debug, Clone, Serialize)]
+pub struct CorrelationTest {
+    pub method: CorrelationMethod,
+    pub r: f64,
+    /// Test statistic: t on `effective_n - 2` degrees of freedom for
+    /// Pearson and Spearman, a standard normal z for Kendall.
+    pub statistic: f64,
+    /// Two-sided p-value of `statistic`.
+    pub p_value: f64,
+    /// Fisher-z confidence interval for `r`.
+    pub ci_lo: f64,
+    pub ci_hi: f64,
+    pub level: f64,
+    pub n: usize,
+    /// Sample size after discounting lag-1 autocorrelation shared by the
+    /// two series (Bretherton et al., 1999). Never exceeds `n`.
+    pub effective_n: f64,
+    /// Set when `effective_n` is below `MIN_RELIABLE_N`; the coefficient
+    /// should not be reported as meaningful.
+    pub small_sample: bool,
+}
+
+/// Raw correlation of two year-aligned series next to correlations that
+/// remove their common dependence on time.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrendCorrelation {
+    pub n: usize,
+    /// Pearson correlation of the values as given.
+    pub raw: f64,
+    /// Pearson correlation of the residuals after regressing each series on
+    /// year. `None` when a series is an exact straight line, so nothing is
+    /// left once the trend is removed.
+    pub detrended: Option<f64>,
+    /// Pearson correlation of year-over-year changes (per year, so gaps are
+    /// respected). `None` when a series changes at a constant rate.
+    pub differenced: Option<f64>,
+    /// Set when `raw` differs from both the detrended and the differenced
+    /// correlation by more than `SHARED_TREND_GAP` (a missing value counts
+    /// as 0): the raw figure reflects two trends, not a relationship.
+    pub shared_trend: bool,
+}
+
+/// Pairwise correlations among several aligned series. Every matrix is
+/// symmetric with one row per series.
+#[derive(Debug, Clone, Serialize)]
+pub struct CorrelationMatrix {
+    pub method: CorrelationMethod,
+    pub n_series: usize,
+    /// Observations per series.
+    pub n_obs: usize,
+    /// Coefficients; 1 on the diagonal.
+    pub r: Vec<Vec<f64>>,
+    /// Unadjusted two-sided p-values, as from `correlation_test`; 0 on the
+    /// diagonal.
+    pub p_value: Vec<Vec<f64>>,
+    /// Benjamini–Hochberg adjusted p-values over the off-diagonal pairs.
+    pub p_bh: Vec<Vec<f64>>,
+    /// Holm adjusted p-values over the off-diagonal pairs.
+    pub p_holm: Vec<Vec<f64>>,
+}
+
+/// Correlation of two series after removing what one or more control
+/// series explain, with its significance test.
+#[derive(Debug, Clone, Serialize)]
+pub struct PartialCorrelation {
+    pub r: f64,
+    /// `r * sqrt(df / (1 - r^2))`.
+    pub t: f64,
+    /// `n - 2 - n_controls`.
+    pub df: f64,
+    pub p_value: f64,
+    /// Fisher-z confidence interval with standard error
+    /// `1 / sqrt(n - 3 - n_controls)`.
+    pub ci_lo: f64,
+    pub ci_hi: f64,
+    pub level: f64,
+    pub n: usize,
+    pub n_controls: usize,
+    /// Set when `df + 2` is below `MIN_RELIABLE_N`.
+    pub small_sample: bool,
+}
+
+/// Correlation of `a` with `b` shifted by one lag.
+#[derive(Debug, Clone, Serialize)]
+pub struct LagCorrelation {
+    /// Years by which `a` leads `b`; negative when `b` leads.
+    pub lag: i32,
+    pub r: f64,
+    /// Two-sided p-value, as from `correlation_test`.
+    pub p_value: f64,
+    /// Year pairs available at this lag.
+    pub n: usize,
+    pub effective_n: f64,
+}
+
+/// Correlations over a range of lags and the strongest of them.
+#[derive(Debug, Clone, Serialize)]
+pub struct CrossCorrelation {
+    /// One entry per lag with at least 4 year pairs, in lag order.
+    pub lags: Vec<LagCorrelation>,
+    /// Lag with the largest `|r|`; ties go to the smaller `|lag|`.
+    pub best_lag: i32,
+    pub best_r: f64,
+    pub best_p_value: f64,
+    /// `best_p_value` Bonferroni-adjusted for the number of lags searched.
+    pub best_p_adjusted: f64,
+}
+
+/// Correlation coefficient of two equal-length samples under `method`.
+pub fn correlation(a: &[f64], b: &[f64], method: CorrelationMethod) -> Result<f64, ComputeError> {
+    match method {
+        CorrelationMethod::Pearson => pearson(a, b),
+        CorrelationMethod::Spearman => spearman(a, b),
+        CorrelationMethod::Kendall => Ok(kendall_counts(a, b)?.tau_b),
+    }
+}
+
+/// Pearson correlation coefficient of two equal-length samples.
+pub fn pearson(a: &[f64], b: &[f64]) -> Result<f64, ComputeError> {
+    require_len("a", a, 1)?;
+    require_same_len("b", b, a.len())?;
+    let n = a.len();
+
+    let n_f = n as f64;
+    let mean_a = a.iter().sum::<f64>() / n_f;
+    let mean_b = b.iter().sum::<f64>() / n_f;
+
+    let mut num = 0.0;
+    let mut den_a = 0.0;
+    let mut den_b = 0.0;
+    for (&x, &y) in a.iter().zip(b) {
+        let da = x - mean_a;
+        let db = y - mean_b;
+        num += da * db;
+        den_a += da * da;
+        den_b += db * db;
+    }
+
+    if den_a == 0.0 {
+        return Err(ComputeError::ZeroVariance { arg: "a" });
+    }
+    if den_b == 0.0 {
+        return Err(ComputeError::ZeroVariance { arg: "b" });
+    }
+    Ok(num / (den_a * den_b).sqrt())
+}
+
+/// Spearman's rank correlation: Pearson correlation of the average ranks,
+/// so tied values share the mean of the ranks they span.
+pub fn spearman(a: &[f64], b: &[f64]) -> Result<f64, ComputeError> {
+    require_len("a", a, 1)?;
+    require_same_len("b", b, a.len())?;
+    pearson(&ranks(a), &ranks(b))
+}
+
+/// Kendall's tau-b. O(n log n) via Knight's merge-sort algorithm.
+pub fn kendall(a: &[f64], b: &[f64]) -> Result<f64, ComputeError> {
+    Ok(kendall_counts(a, b)?.tau_b)
+}
+
+/// 1-based ranks of `values`, averaging over ties.
+pub fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&i, &j| values[i].total_cmp(&values[j]));
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        // Positions start..end hold ranks start+1..=end; share their mean.
+        let rank = (start + end + 1) as f64 / 2.0;
+        for &i in &order[start..end] {
+            ranks[i] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
+
+/// Pieces of Kendall's tau-b and its null variance.
+struct KendallCounts {
+    tau_b: f64,
+    /// Concordant minus discordant pairs.
+    s: f64,
+    /// Variance of `s` under independence, corrected for ties.
+    var_s: f64,
+}
+
+fn kendall_counts(a: &[f64], b: &[f64]) -> Result<KendallCounts, ComputeError> {
+    require_len("a", a, 2)?;
+    require_same_len("b", b, a.len())?;
+    let n = a.len();
+
+    let mut pairs: Vec<(f64, f64)> = a.iter().copied().zip(b.iter().copied()).collect();
+    pairs.sort_by(|p, q| p.0.total_cmp(&q.0).then(p.1.total_cmp(&q.1)));
+
+    // Ties in a, and joint ties in (a, b), from the (a, b)-sorted order.
+    let a_ties = tie_groups(&pairs, |p, q| p.0 == q.0);
+    let joint_ties = tie_groups(&pairs, |p, q| p == q);
+
+    // Sorting by b with a stable merge sort counts the discordant pairs as
+    // the number of swaps; pairs tied in a are already ordered by b.
+    let mut ys: Vec<f64> = pairs.iter().map(|p| p.1).collect();
+    let mut buf = vec![0.0; n];
+    let swaps = merge_count(&mut ys, &mut buf) as f64;
+    let b_ties = tie_groups(&ys, |p, q| p == q);
+
+    let pairs_of = |groups: &[usize]| -> f64 {
+        groups
+            .iter()
+            .map(|&t| (t * (t - 1)) as f64 / 2.0)
+            .sum::<f64>()
+    };
+    let n0 = (n * (n - 1)) as f64 / 2.0;
+    let n1 = pairs_of(&a_ties);
+    let n2 = pairs_of(&b_ties);
+    let n3 = pairs_of(&joint_ties);
+    if n1 == n0 {
+        return Err(ComputeError::ZeroVariance { arg: "a" });
+    }
+    if n2 == n0 {
+        return Err(ComputeError::ZeroVariance { arg: "b" });
+    }
+    let s = n0 - n1 - n2 + n3 - 2.0 * swaps;
+    let tau_b = s / ((n0 - n1) * (n0 - n2)).sqrt();
+
+    // Kendall (1970) variance of S with ties in both rankings.
+    let nf = n as f64;
+    let sum_over = |groups: &[usize], f: &dyn Fn(f64) -> f64| -> f64 {
+        groups.iter().map(|&t| f(t as f64)).sum()
+    };
+    let v0 = nf * (nf - 1.0) * (2.0 * nf + 5.0);
+    let vt = sum_over(&a_ties, &|t| t * (t - 1.0) * (2.0 * t + 5.0));
+    let vu = sum_over(&b_ties, &|u| u * (u - 1.0) * (2.0 * u + 5.0));
+    let v1 = sum_over(&a_ties, &|t| t * (t - 1.0)) * sum_over(&b_ties, &|u| u * (u - 1.0))
+        / (2.0 * nf * (nf - 1.0));
+    let v2 = if n > 2 {
+        sum_over(&a_ties, &|t| t * (t - 1.0) * (t - 2.0))
+            * sum_over(&b_ties, &|u| u * (u - 1.0) * (u - 2.0))
+            / (9.0 * nf * (nf - 1.0) * (nf - 2.0))
+    } else {
+        0.0
+    };
+    let var_s = (v0 - vt - vu) / 18.0 + v1 + v2;
+
+    Ok(KendallCounts { tau_b, s, var_s })
+}
+
+/// Sizes of runs of equal neighbours in an already sorted slice. Runs of
+/// length 1 are omitted.
+fn tie_groups<T>(sorted: &[T], same: impl Fn(&T, &T) -> bool) -> Vec<usize> {
+    let mut groups = Vec::new();
+    let mut run = 1;
+    for w in sorted.windows(2) {
+        if same(&w[0], &w[1]) {
+            run += 1;
+        } else {
+            if run > 1 {
+                groups.push(run);
+            }
+            run = 1;
+        }
+    }
+    if run > 1 {
+        groups.push(run);
+    }
+    groups
+}
+
+/// Stable merge sort of `v`, returning the number of inversions (pairs
+/// with `i < j` and `v[i] > v[j]`).
+fn merge_count(v: &mut [f64], buf: &mut [f64]) -> u64 {
+    let n = v.len();
+    if n < 2 {
+        return 0;
+    }
+    let mid = n / 2;
+    let mut swaps = merge_count(&mut v[..mid], &mut buf[..mid]);
+    swaps += merge_count(&mut v[mid..], &mut buf[mid..]);
+
+    let (mut i, mut j, mut k) = (0, mid, 0);
+    while i < mid && j < n {
+        if v[j] < v[i] {
+            // v[j] jumps ahead of every element left in the left half.
+            buf[k] = v[j];
+            swaps += (mid - i) as u64;
+            j += 1;
+        } else {
+            buf[k] = v[i];
+            i += 1;
+        }
+        k += 1;
+    }
+    buf[k..k + mid - i].copy_from_slice(&v[i..mid]);
+    k += mid - i;
+    buf[k..k + n - j].copy_from_slice(&v[j..n]);
+    v.copy_from_slice(&buf[..n]);
+    swaps
+}
+
+/// Lag-1 autocorrelation of `values` around their mean.
+fn lag1_autocorrelation(values: &[f64]) -> f64 {
+    let n = values.len() as f64;
+    let m = values.iter().sum::<f64>() / n;
+    let den: f64 = values.iter().map(|v| (v - m) * (v - m)).sum();
+    if den == 0.0 {
+        return 0.0;
+    }
+    let num: f64 = values.windows(2).map(|w| (w[0] - m) * (w[1] - m)).sum();
+    num / den
+}
+
+/// Correlation under `method` with a significance test, Fisher-z interval
+/// at `level` and an autocorrelation-adjusted effective sample size.
+///
+/// Trending series are strongly autocorrelated, so `n` overstates the
+/// information they carry; the statistic, p-value and interval all use
+/// `effective_n` (floored at 4 so the statistics stay defined).
+///
+/// Pearson and Spearman use the t test on `effective_n - 2` degrees of
+/// freedom. Kendall uses the tie-corrected normal approximation to its
+/// null distribution, with the variance inflated by `n / effective_n`.
+/// Interval standard errors for the rank methods follow Fieller, Hartley
+/// and Pearson (1957).
+pub fn correlation_test(
+    a: &[f64],
+    b: &[f64],
+    method: CorrelationMethod,
+    level: f64,
+) -> Result<CorrelationTest, ComputeError> {
+    require_level(level)?;
+    require_len("a", a, 4)?;
+    require_same_len("b", b, a.len())?;
+    let n = a.len();
+    let n_eff = effective_n(a, b);
+
+    let (r, statistic, p_value) = match method {
+        CorrelationMethod::Pearson | CorrelationMethod::Spearman => {
+            let r = correlation(a, b, method)?;
+            let (t, p) = t_test(r, n_eff);
+            (r, t, p)
+        }
+        CorrelationMethod::Kendall => {
+            let k = kendall_counts(a, b)?;
+            let z = k.s / (k.var_s * n as f64 / n_eff).sqrt();
+            (k.tau_b, z, normal_two_sided_p(z))
+        }
+    };
+    let se_z = match method {
+        CorrelationMethod::Pearson => 1.0 / (n_eff - 3.0).sqrt(),
+        CorrelationMethod::Spearman => (1.06 / (n_eff - 3.0)).sqrt(),
+        CorrelationMethod::Kendall => (0.437 / (n_eff - 4.0)).sqrt(),
+    };
+    let (ci_lo, ci_hi) = fisher_interval(r, se_z, level);
+
+    Ok(CorrelationTest {
+        method,
+        r,
+        statistic,
+        p_value,
+        ci_lo,
+        ci_hi,
+        level,
+        n,
+        effective_n: n_eff,
+        small_sample: n_eff < MIN_RELIABLE_N,
+    })
+}
+
+/// `n (1 - r1a r1b) / (1 + r1a r1b)` clamped to `[4, n]`.
+pub(crate) fn effective_n(a: &[f64], b: &[f64]) -> f64 {
+    let n = a.len() as f64;
+    let rho = lag1_autocorrelation(a) * lag1_autocorrelation(b);
+    (n * (1.0 - rho) / (1.0 + rho)).clamp(4.0_f64.min(n), n)
+}
+
+/// t statistic `r * sqrt(df / (1 - r^2))` on `n - 2` degrees of freedom,
+/// and its two-sided p-value.
+pub(crate) fn t_test(r: f64, n: f64) -> (f64, f64) {
+    let df = n - 2.0;
+    let t = r * (df / (1.0 - r * r)).sqrt();
+    let p = if t.is_infinite() {
+        0.0
+    } else {
+        dist::student_t_two_sided_p(t, df)
+    };
+    (t, p)
+}
+
+/// Fisher-z interval for `r` given the standard error of `atanh(r)`.
+pub(crate) fn fisher_interval(r: f64, se_z: f64, level: f64) -> (f64, f64) {
+    let z = r.atanh();
+    let half_width = dist::normal_quantile(0.5 + level / 2.0) * se_z;
+    ((z - half_width).tanh(), (z + half_width).tanh())
+}
+
+fn normal_two_sided_p(z: f64) -> f64 {
+    2.0 * dist::normal_cdf(-z.abs())
+}
+
+/// Compare the raw correlation of two aligned series with their detrended
+/// and first-difference correlations to expose spurious trend correlation.
+pub fn trend_correlation(
+    years: &[f64],
+    a: &[f64],
+    b: &[f64],
+) -> Result<TrendCorrelation, ComputeError> {
+    require_len("a", a, 4)?;
+    require_same_len("b", b, a.len())?;
+    require_same_len("years", years, a.len())?;
+    let raw = pearson(a, b)?;
+
+    let residuals = |v: &[f64]| -> Result<Vec<f64>, ComputeError> {
+        Ok(regression::linear_fit(years, v)?.residuals)
+    };
+    let detrended = residual_correlation(a, b, &residuals(a)?, &residuals(b)?);
+
+    let per_year = |v: &[f64]| -> Vec<f64> {
+        v.windows(2)
+            .zip(years.windows(2))
+            .map(|(v, y)| (v[1] - v[0]) / (y[1] - y[0]))
+            .collect()
+    };
+    let differenced = residual_correlation(a, b, &per_year(a), &per_year(b));
+
+    let gap = |r: Option<f64>| (raw - r.unwrap_or(0.0)).abs() > SHARED_TREND_GAP;
+    Ok(TrendCorrelation {
+        n: a.len(),
+        raw,
+        detrended,
+        differenced,
+        shared_trend: gap(detrended) && gap(differenced),
+    })
+}
+
+/// Correlation of `da` and `db`, series derived from `a` and `b`. `None`
+/// if either derived series has no variation left relative to its source;
+/// that avoids correlating floating-point noise.
+fn residual_correlation(a: &[f64], b: &[f64], da: &[f64], db: &[f64]) -> Option<f64> {
+    const REL_TOL: f64 = 1e-9;
+    let spread = |v: &[f64]| {
+        let m = v.iter().sum::<f64>() / v.len() as f64;
+        v.iter().map(|x| (x - m) * (x - m)).sum::<f64>().sqrt()
+    };
+    if spread(da) <= REL_TOL * spread(a) || spread(db) <= REL_TOL * spread(b) {
+        return None;
+    }
+    pearson(da, db).ok()
+}
+
+/// Correlate every pair of `series`, which must all have the same length
+/// and be aligned on the same years, and adjust the p-values for the
+/// `k (k - 1) / 2` tests performed.
+pub fn correlation_matrix(
+    series: &[Vec<f64>],
+    method: CorrelationMethod,
+) -> Result<CorrelationMatrix, ComputeError> {
+    const ARG: &str = "values";
+    let k = series.len();
+    if k < 2 {
+        return Err(ComputeError::TooShort {
+            arg: "n_series",
+            min: 2,
+            len: k,
+        });
+    }
+    let n_obs = series[0].len();
+    for s in series {
+        require_same_len(ARG, s, n_obs)?;
+    }
+
+    let mut r = vec![vec![1.0; k]; k];
+    let mut p_value = vec![vec![0.0; k]; k];
+    let mut pairs = Vec::with_capacity(k * (k - 1) / 2);
+    for i in 0..k {
+        for j in i + 1..k {
+            let test = correlation_test(&series[i], &series[j], method, 0.95)?;
+            r[i][j] = test.r;
+            r[j][i] = test.r;
+            p_value[i][j] = test.p_value;
+            p_value[j][i] = test.p_value;
+            pairs.push((i, j));
+        }
+    }
+
+    let flat: Vec<f64> = pairs.iter().map(|&(i, j)| p_value[i][j]).collect();
+    let spread = |adjusted: Vec<f64>| {
+        let mut m = vec![vec![0.0; k]; k];
+        for (&(i, j), p) in pairs.iter().zip(adjusted) {
+            m[i][j] = p;
+            m[j][i] = p;
+        }
+        m
+    };
+    Ok(CorrelationMatrix {
+        method,
+        n_series: k,
+        n_obs,
+        r,
+        p_bh: spread(multiple::adjust(&flat, PAdjustMethod::BenjaminiHochberg)),
+        p_holm: spread(multiple::adjust(&flat, PAdjustMethod::Holm)),
+        p_value,
+    })
+}
+
+/// Partial Pearson correlation of `x` and `y` controlling for `controls`.
+///
+/// Both series are regressed on an intercept plus every control and the
+/// residuals are correlated. Each control costs a degree of freedom.
+pub fn partial_correlation(
+    x: &[f64],
+    y: &[f64],
+    controls: &[Vec<f64>],
+    level: f64,
+) -> Result<PartialCorrelation, ComputeError> {
+    require_level(level)?;
+    let k = controls.len();
+    require_len("x", x, k + 4)?;
+    require_same_len("y", y, x.len())?;
+    for c in controls {
+        require_same_len("controls", c, x.len())?;
+    }
+    let n = x.len();
+
+    let columns: Vec<&[f64]> = controls.iter().map(Vec::as_slice).collect();
+    let design = Matrix::from_columns(n, &columns, true);
+    let residualize = |v: &[f64]| {
+        linalg::lstsq(&design, v)
+            .map(|fit| fit.residuals)
+            .ok_or(ComputeError::Singular { arg: "controls" })
+    };
+    let rx = residualize(x)?;
+    let ry = residualize(y)?;
+    let r = pearson(&rx, &ry).map_err(|err| match err {
+        // Residuals with no spread mean the controls explain x or y fully.
+        ComputeError::ZeroVariance { arg: "a" } => ComputeError::ZeroVariance { arg: "x" },
+        ComputeError::ZeroVariance { .. } => ComputeError::ZeroVariance { arg: "y" },
+        err => err,
+    })?;
+
+    // t_test takes a sample size and subtracts the two usual degrees of
+    // freedom itself.
+    let (t, p_value) = t_test(r, (n - k) as f64);
+    let (ci_lo, ci_hi) = fisher_interval(r, 1.0 / ((n - k) as f64 - 3.0).sqrt(), level);
+    let df = (n - k) as f64 - 2.0;
+    Ok(PartialCorrelation {
+        r,
+        t,
+        df,
+        p_value,
+        ci_lo,
+        ci_hi,
+        level,
+        n,
+        n_controls: k,
+        small_sample: df + 2.0 < MIN_RELIABLE_N,
+    })
+}
+
+/// Pearson correlation of `a` at year `t` with `b` at year `t + lag`, for
+/// every whole-year lag in `min_lag..=max_lag`.
+///
+/// Pairs are matched on actual years, so a gap in either series drops only
+/// the pairs that need the missing year. Lags with fewer than 4 pairs are
+/// left out, and lags beyond the years the two series span are never
+/// tried, however wide the requested range.
+pub fn cross_correlation(
+    a: &YearSeries,
+    b: &YearSeries,
+    min_lag: i32,
+    max_lag: i32,
+) -> Result<CrossCorrelation, ComputeError> {
+    require_range("max_lag", max_lag as f64, min_lag as f64, f64::INFINITY)?;
+    let first = |s: &YearSeries| s.years().first().copied().unwrap_or(0.0);
+    let last = |s: &YearSeries| s.years().last().copied().unwrap_or(0.0);
+    // Float-to-int casts saturate, so far-off years cannot overflow.
+    let min_lag = min_lag.max((first(b) - last(a)).floor() as i32);
+    let max_lag = max_lag.min((last(b) - first(a)).ceil() as i32);
+
+    let mut lags = Vec::new();
+    for lag in min_lag..=max_lag {
+        let (xs, ys): (Vec<f64>, Vec<f64>) = a
+            .years()
+            .iter()
+            .zip(a.values())
+            .filter_map(|(&year, &x)| b.get(year + lag as f64).map(|y| (x, y)))
+            .unzip();
+        if xs.len() < 4 {
+            continue;
+        }
+        let test = correlation_test(&xs, &ys, CorrelationMethod::Pearson, 0.95)?;
+        lags.push(LagCorrelation {
+            lag,
+            r: test.r,
+            p_value: test.p_value,
+            n: test.n,
+            effective_n: test.effective_n,
+        });
+    }
+
+    let (best_lag, best_r, best_p_value) = lags
+        .iter()
+        .reduce(|best, l| {
+            let (lr, br) = (l.r.abs(), best.r.abs());
+            if lr > br || (lr == br && l.lag.abs() < best.lag.abs()) {
+                l
+            } else {
+                best
+            }
+        })
+        .map(|best| (best.lag, best.r, best.p_value))
+        .ok_or(ComputeError::TooShort {
+            arg: "years_b",
+            min: 4,
+            len: 0,
+        })?;
+
+    Ok(CrossCorrelation {
+        best_lag,
+        best_r,
+        best_p_value,
+        best_p_adjusted: (best_p_value * lags.len() as f64).min(1.0),
+        lags,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    const A: [f64; 8] = [1.0, 2.0, 2.0, 3.0, 4.0, 4.0, 4.0, 5.0];
+    const B: [f64; 8] = [2.0, 1.0, 3.0, 3.0, 5.0, 4.0, 5.0, 6.0];
+
+    #[test]
+    fn t_test_and_fisher_interval() {
+        let (t, p) = t_test(0.5, 20.0);
+        assert_close(t, 6.0f64.sqrt(), 1e-12);
+        // 2 * pt(-sqrt(6), 18) in R.
+        assert_close(p, 0.024_769_558_804_109_69, 1e-10);
+        let (lo, hi) = fisher_interval(0.5, 1.0 / 17.0f64.sqrt(), 0.95);
+        assert_close(lo, 0.073_810_574_407_409_75, 1e-10);
+        assert_close(hi, 0.771_760_706_429_918_8, 1e-10);
+    }
+
+    #[test]
+    fn correlation_test_discounts_autocorrelation() {
+        let a = [2.0, 5.0, 1.0, 6.0, 3.0, 8.0, 4.0, 9.0];
+        let b = [1.0, 4.0, 2.0, 5.0, 2.0, 7.0, 5.0, 8.0];
+        let test = correlation_test(&a, &b, CorrelationMethod::Pearson, 0.9).unwrap();
+        assert_eq!(test.n, 8);
+        assert_close(test.r, 0.946_371_891_852_628_5, 1e-12);
+        // Lag-1 autocorrelations -0.35698 and -0.07615.
+        assert_close(test.effective_n, 7.576_567_026_174_728, 1e-12);
+        assert_close(test.statistic, 6.917_292_585_662_395, 1e-10);
+        assert_close(test.p_value, 0.000_617_246_870_635_017_1, 1e-8);
+        assert_close(test.ci_lo, 0.772_680_856_127_812_3, 1e-10);
+        assert_close(test.ci_hi, 0.988_229_514_842_081_2, 1e-10);
+        assert!(test.small_sample);
+    }
+
+    #[test]
+    fn effective_n_is_clamped() {
+        // A shared trend would push the estimate below 4.
+        let line: Vec<f64> = (0..12).map(f64::from).collect();
+        assert_eq!(effective_n(&line, &line), 4.0);
+        let alternating: Vec<f64> = (0..12).map(|i| (i % 2) as f64).collect();
+        assert_eq!(effective_n(&alternating, &line), 12.0);
+    }
+
+    #[test]
+    fn correlation_test_rejects_bad_input() {
+        let a = [1.0, 2.0, 4.0, 3.0];
+        let b = [2.0, 1.0, 3.0, 5.0];
+        for level in [0.0, 1.0, 1.5] {
+            assert_eq!(
+                correlation_test(&a, &b, CorrelationMethod::Pearson, level).unwrap_err(),
+                ComputeError::OutOfRange {
+                    arg: "level",
+                    value: level,
+                    min: 0.0,
+                    max: 1.0
+                }
+            );
+        }
+        assert_eq!(
+            correlation_test(&a[..3], &b[..3], CorrelationMethod::Pearson, 0.95).unwrap_err(),
+            ComputeError::TooShort {
+                arg: "a",
+                min: 4,
+                len: 3
+            }
+        );
+    }
+
+    #[test]
+    fn ranks_average_ties() {
+        assert_eq!(ranks(&A), [1.0, 2.5, 2.5, 4.0, 6.0, 6.0, 6.0, 8.0]);
+        assert_eq!(ranks(&B), [2.0, 1.0, 3.5, 3.5, 6.5, 5.0, 6.5, 8.0]);
+    }
+
+    #[test]
+    fn tied_coefficients_match_r() {
+        // cor(a, b, method = ...) in R.
+        assert_close(pearson(&A, &B).unwrap(), 0.898_629_488_478_113_1, 1e-12);
+        assert_close(spearman(&A, &B).unwrap(), 0.925_626_545_313_669, 1e-12);
+        assert_close(kendall(&A, &B).unwrap(), 0.840_672_807_476_707_5, 1e-12);
+    }
+
+    /// Tau-b straight from its definition, counting every pair.
+    fn kendall_pairwise(a: &[f64], b: &[f64]) -> f64 {
+        let (mut concordant, mut discordant, mut ties_a, mut ties_b) = (0.0f64, 0.0, 0.0, 0.0);
+        for i in 0..a.len() {
+            for j in i + 1..a.len() {
+                let (da, db) = (a[i] - a[j], b[i] - b[j]);
+                match (da == 0.0, db == 0.0) {
+                    (true, true) => {}
+                    (true, false) => ties_a += 1.0,
+                    (false, true) => ties_b += 1.0,
+                    _ if da * db > 0.0 => concordant += 1.0,
+                    _ => discordant += 1.0,
+                }
+            }
+        }
+        let pairs = concordant + discordant;
+        (concordant - discordant) / ((pairs + ties_a) * (pairs + ties_b)).sqrt()
+    }
+
+    #[test]
+    fn kendall_merge_sort_matches_pairwise_count() {
+        let mut state: u64 = 7;
+        let mut draw = || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 33) % 6) as f64
+        };
+        let a: Vec<f64> = (0..60).map(|_| draw()).collect();
+        let b: Vec<f64> = a.iter().map(|x| x + draw()).collect();
+        assert_close(kendall(&a, &b).unwrap(), kendall_pairwise(&a, &b), 1e-12);
+    }
+
+    #[test]
+    fn constant_input_has_no_correlation() {
+        let flat = [3.0; 8];
+        for method in [
+            CorrelationMethod::Pearson,
+            CorrelationMethod::Spearman,
+            CorrelationMethod::Kendall,
+        ] {
+            assert!(correlation(&flat, &B, method).is_err());
+        }
+    }
+
+    const GAPPY_YEARS: [f64; 8] = [
+        2000.0, 2001.0, 2002.0, 2004.0, 2005.0, 2007.0, 2008.0, 2010.0,
+    ];
+
+    #[test]
+    fn independent_trends_are_flagged() {
+        // Two upward trends plus unrelated noise, on gappy years.
+        let a = [0.3, 0.8, 2.1, 3.7, 5.2, 7.0, 7.9, 10.2];
+        let b = [4.9, 7.2, 9.2, 12.8, 14.9, 19.3, 21.0, 24.8];
+        let trend = trend_correlation(&GAPPY_YEARS, &a, &b).unwrap();
+        assert_eq!(trend.n, 8);
+        assert_close(trend.raw, 0.997_553_830_666_550_6, 1e-12);
+        assert_close(trend.detrended.unwrap(), -0.181_736_920_876_912_55, 1e-10);
+        assert_close(trend.differenced.unwrap(), -0.149_831_644_806_211_58, 1e-10);
+        assert!(trend.shared_trend);
+    }
+
+    #[test]
+    fn exact_lines_have_nothing_left_to_correlate() {
+        let a: Vec<f64> = GAPPY_YEARS.iter().map(|y| 3.0 * y - 10.0).collect();
+        let b: Vec<f64> = GAPPY_YEARS.iter().map(|y| 50.0 - 0.5 * y).collect();
+        let trend = trend_correlation(&GAPPY_YEARS, &a, &b).unwrap();
+        assert_close(trend.raw, -1.0, 1e-12);
+        assert_eq!((trend.detrended, trend.differenced), (None, None));
+        assert!(trend.shared_trend);
+    }
+
+    #[test]
+    fn untrended_correlation_is_not_flagged() {
+        let a = [1.0, 3.0, 2.0, 5.0, 4.0, 1.0, 3.0, 2.0];
+        let b = [2.0, 4.0, 2.0, 6.0, 5.0, 1.0, 4.0, 2.0];
+        let trend = trend_correlation(&GAPPY_YEARS, &a, &b).unwrap();
+        assert!(trend.raw > 0.9);
+        assert!(!trend.shared_trend);
+    }
+
+    #[test]
+    fn correlation_matrix_adjusts_every_pair() {
+        let c = [5.0, 3.0, 4.0, 1.0, 2.0, 2.0, 1.0, 0.0];
+        let series = vec![A.to_vec(), B.to_vec(), c.to_vec()];
+        let m = correlation_matrix(&series, CorrelationMethod::Spearman).unwrap();
+        assert_eq!((m.n_series, m.n_obs), (3, 8));
+
+        let pairs = [(0, 1), (0, 2), (1, 2)];
+        let mut raw = Vec::new();
+        for (i, j) in pairs {
+            let test = correlation_test(&series[i], &series[j], CorrelationMethod::Spearman, 0.95)
+                .unwrap();
+            assert_eq!(m.r[i][j], test.r);
+            assert_eq!(m.r[j][i], test.r);
+            assert_eq!(m.p_value[j][i], test.p_value);
+            raw.push(test.p_value);
+        }
+        let bh = multiple::benjamini_hochberg(&raw);
+        let holm = multiple::holm(&raw);
+        for (k, (i, j)) in pairs.into_iter().enumerate() {
+            assert_eq!((m.p_bh[i][j], m.p_bh[j][i]), (bh[k], bh[k]));
+            assert_eq!((m.p_holm[i][j], m.p_holm[j][i]), (holm[k], holm[k]));
+        }
+        for i in 0..3 {
+            assert_eq!((m.r[i][i], m.p_value[i][i]), (1.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn correlation_matrix_rejects_bad_shapes() {
+        assert_eq!(
+            correlation_matrix(&[A.to_vec()], CorrelationMethod::Pearson).unwrap_err(),
+            ComputeError::TooShort {
+                arg: "n_series",
+                min: 2,
+                len: 1
+            }
+        );
+        assert_eq!(
+            correlation_matrix(&[A.to_vec(), B[..7].to_vec()], CorrelationMethod::Pearson)
+                .unwrap_err(),
+            ComputeError::LengthMismatch {
+                arg: "values",
+                expected: 8,
+                len: 7
+            }
+        );
+    }
+
+    const X: [f64; 10] = [3.0, 5.0, 4.0, 8.0, 7.0, 9.0, 6.0, 10.0, 12.0, 11.0];
+    const Y: [f64; 10] = [2.0, 4.0, 5.0, 5.0, 8.0, 7.0, 9.0, 8.0, 11.0, 13.0];
+    const Z: [f64; 10] = [1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0, 5.0, 6.0, 6.0];
+    const W: [f64; 10] = [5.0, 3.0, 4.0, 2.0, 4.0, 1.0, 3.0, 2.0, 1.0, 0.0];
+
+    #[test]
+    fn partial_correlation_matches_first_order_formula() {
+        let (rxy, rxz, ryz) = (
+            pearson(&X, &Y).unwrap(),
+            pearson(&X, &Z).unwrap(),
+            pearson(&Y, &Z).unwrap(),
+        );
+        let formula = (rxy - rxz * ryz) / ((1.0 - rxz * rxz) * (1.0 - ryz * ryz)).sqrt();
+
+        let partial = partial_correlation(&X, &Y, &[Z.to_vec()], 0.95).unwrap();
+        assert_close(rxy, 0.816_347_517_196_510_7, 1e-12);
+        assert_close(partial.r, formula, 1e-12);
+        assert_close(partial.r, -0.518_043_553_788_279_7, 1e-12);
+        // n - 2 - k degrees of freedom.
+        assert_eq!((partial.df, partial.n, partial.n_controls), (7.0, 10, 1));
+        assert_close(partial.t, -1.602_393_893_742_371_9, 1e-10);
+        assert_close(partial.p_value, 0.153_102_714_118_588_8, 1e-10);
+        assert_close(partial.ci_lo, -0.879_558_473_045_562_1, 1e-10);
+        assert_close(partial.ci_hi, 0.222_695_039_599_962_63, 1e-10);
+    }
+
+    #[test]
+    fn partial_correlation_matches_inverse_correlation_matrix() {
+        // -P[x, y] / sqrt(P[x, x] P[y, y]) for P the inverse of the
+        // correlation matrix of x, y, z and w.
+        let controls = [Z.to_vec(), W.to_vec()];
+        let partial = partial_correlation(&X, &Y, &controls, 0.95).unwrap();
+        assert_close(partial.r, -0.426_947_972_026_318_76, 1e-12);
+        assert_eq!(partial.df, 6.0);
+        assert_close(partial.t, -1.156_510_244_173_294_5, 1e-10);
+        assert_close(partial.p_value, 0.291_435_155_722_003_97, 1e-10);
+        assert_close(partial.ci_lo, -0.869_903_026_230_601_7, 1e-10);
+        assert_close(partial.ci_hi, 0.397_237_221_669_131_8, 1e-10);
+    }
+
+    #[test]
+    fn partial_correlation_rejects_collinear_controls() {
+        let doubled: Vec<f64> = Z.iter().map(|z| 2.0 * z).collect();
+        assert_eq!(
+            partial_correlation(&X, &Y, &[Z.to_vec(), doubled], 0.95).unwrap_err(),
+            ComputeError::Singular { arg: "controls" }
+        );
+        assert_eq!(
+            partial_correlation(&X, &Y, &[Z.to_vec()], 1.0)
+                .unwrap_err()
+                .code(),
+            "OUT_OF_RANGE"
+        );
+    }
+
+    /// An untrended series on gappy years, and a copy that lags it by three
+    /// years with some of its own years missing.
+    fn shifted_pair() -> (YearSeries, YearSeries) {
+        let years: Vec<f64> = (1990..2020).filter(|y| y % 7 != 3).map(f64::from).collect();
+        let mut state: u64 = 11;
+        let values: Vec<f64> = years
+            .iter()
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((state >> 33) % 100) as f64
+            })
+            .collect();
+        let (later_years, later_values): (Vec<f64>, Vec<f64>) = years
+            .iter()
+            .zip(&values)
+            .filter(|(y, _)| **y as i32 % 5 != 0)
+            .map(|(y, v)| (y + 3.0, *v))
+            .unzip();
+        (
+            YearSeries::new(&years, &values),
+            YearSeries::new(&later_years, &later_values),
+        )
+    }
+
+    #[test]
+    fn cross_correlation_finds_the_shift_in_years() {
+        let (a, b) = shifted_pair();
+        let cross = cross_correlation(&a, &b, -6, 6).unwrap();
+        assert_eq!(cross.best_lag, 3);
+        assert_close(cross.best_r, 1.0, 1e-12);
+        let at = |lag: i32| cross.lags.iter().find(|l| l.lag == lag).unwrap();
+        // Every year of `b` pairs with `a` three years earlier.
+        assert_eq!(at(3).n, b.len());
+        assert!(at(2).r.abs() < 0.9 && at(4).r.abs() < 0.9);
+        assert_eq!(cross.lags.len(), 13);
+    }
+
+    #[test]
+    fn cross_correlation_only_tries_lags_the_years_allow() {
+        let (a, b) = shifted_pair();
+        let cross = cross_correlation(&a, &b, i32::MIN, i32::MAX).unwrap();
+        assert_eq!(cross.best_lag, 3);
+        // 1990..=2018 against 1995..=2021 reach lags -23 to 31, and the
+        // extremes have too few pairs to keep.
+        let lags: Vec<i32> = cross.lags.iter().map(|l| l.lag).collect();
+        assert!(lags[0] > -23 && lags[lags.len() - 1] < 31);
+        assert_eq!(
+            cross_correlation(&a, &b, 60, 90).unwrap_err(),
+            ComputeError::TooShort {
+                arg: "years_b",
+                min: 4,
+                len: 0
+            }
+        );
+        assert_eq!(
+            cross_correlation(&a, &b, 2, 1).unwrap_err().code(),
+            "OUT_OF_RANGE"
+        );
+    }
+}
@@ -0,0 +1,53 @@
+//! Information criteria for choosing between fitted models.
+
+use wasm_bindgen::prelude::*;
+
+/// Criterion used to choose a model order; lower is better.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InformationCriterion {
+    /// Akaike information criterion.
+    #[default]
+    Aic = 0,
+    /// Schwarz (Bayesian) information criterion; penalizes extra
+    /// parameters more heavily once n > 7.
+    Bic = 1,
+    /// AIC with the small-sample correction of Hurvich and Tsai.
+    Aicc = 2,
+}
+
+impl InformationCriterion {
+    /// Value of the criterion for a model with maximized log-likelihood
+    /// `log_lik`, `k` estimated parameters and `n` observations.
+    pub fn value(self, log_lik: f64, k: usize, n: usize) -> f64 {
+        match self {
+            InformationCriterion::Aic => aic(log_lik, k),
+            InformationCriterion::Bic => bic(log_lik, k, n),
+            InformationCriterion::Aicc => aicc(log_lik, k, n),
+        }
+    }
+}
+
+pub fn aic(log_lik: f64, k: usize) -> f64 {
+    -2.0 * log_lik + 2.0 * k as f64
+}
+
+pub fn bic(log_lik: f64, k: usize, n: usize) -> f64 {
+    -2.0 * log_lik + k as f64 * (n as f64).ln()
+}
+
+/// AICc; infinite when `n <= k + 1`, where the correction is undefined.
+pub fn aicc(log_lik: f64, k: usize, n: usize) -> f64 {
+    let (k, n) = (k as f64, n as f64);
+    if n <= k + 1.0 {
+        return f64::INFINITY;
+    }
+    aic(log_lik, k as usize) + 2.0 * k * (k + 1.0) / (n - k - 1.0)
+}
+
+/// Gaussian log-likelihood at the maximum for `n` residuals with sum of
+/// squares `sse`.
+pub fn gaussian_log_lik(sse: f64, n: usize) -> f64 {
+    let n = n as f64;
+    -0.5 * n * ((2.0 * std::f64::consts::PI * sse / n).ln() + 1.0)
+}
@@ -0,0 +1,120 @@
+use serde::Serialize;
+
+use crate::dist;
+use crate::error::{require_len, require_level, require_same_len, ComputeError};
+use crate::regression;
+
+/// Constant elasticity of `y` with respect to `x` from a log-log fit.
+#[derive(Debug, Clone, Serialize)]
+pub struct Elasticity {
+    /// Percent change in `y` associated with a 1% change in `x`.
+    pub elasticity: f64,
+    pub std_error: f64,
+    /// t interval on the elasticity at `level`.
+    pub ci_lo: f64,
+    pub ci_hi: f64,
+    pub level: f64,
+    /// Two-sided p-value for zero elasticity.
+    pub p_value: f64,
+    /// Fit of `ln y` on `ln x`.
+    pub r_squared: f64,
+    pub n: usize,
+}
+
+/// Fit `ln y = a + b ln x` over paired observations and report `b` with a
+/// t confidence interval at `level`.
+///
+/// Pairs can be one geography over time or many geographies in one year;
+/// either way every value must be positive.
+pub fn elasticity(x: &[f64], y: &[f64], level: f64) -> Result<Elasticity, ComputeError> {
+    require_level(level)?;
+    require_len("x", x, 3)?;
+    require_same_len("y", y, x.len())?;
+    let log_x = logs("x", x)?;
+    let log_y = logs("y", y)?;
+    // Lengths are checked, so the only failure left is a constant `x`.
+    let fit = regression::linear_fit(&log_x, &log_y)
+        .map_err(|_| ComputeError::ZeroVariance { arg: "x" })?;
+
+    let df = (fit.n - 2) as f64;
+    let half_width = dist::student_t_quantile(0.5 + level / 2.0, df) * fit.slope_std_error;
+    Ok(Elasticity {
+        elasticity: fit.slope,
+        std_error: fit.slope_std_error,
+        ci_lo: fit.slope - half_width,
+        ci_hi: fit.slope + half_width,
+        level,
+        p_value: dist::student_t_two_sided_p(fit.slope / fit.slope_std_error, df),
+        r_squared: fit.r_squared,
+        n: fit.n,
+    })
+}
+
+fn logs(arg: &'static str, values: &[f64]) -> Result<Vec<f64>, ComputeError> {
+    values
+        .iter()
+        .map(|&v| {
+            if v > 0.0 {
+                Ok(v.ln())
+            } else {
+                Err(ComputeError::NonPositive { arg })
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    #[test]
+    fn power_law_recovers_exponent() {
+        let x = [1.0, 2.0, 5.0, 10.0, 40.0];
+        let y: Vec<f64> = x.iter().map(|x: &f64| 3.0 * x.powf(-0.75)).collect();
+        let e = elasticity(&x, &y, 0.95).unwrap();
+        assert_close(e.elasticity, -0.75, 1e-12);
+        assert_close(e.r_squared, 1.0, 1e-12);
+        assert!(e.std_error < 1e-12);
+    }
+
+    #[test]
+    fn across_geographies() {
+        // One year, six geographies in no particular order.
+        let x = [52.0, 8.5, 130.0, 17.0, 33.0, 71.0];
+        let y = [410.0, 120.0, 900.0, 190.0, 300.0, 520.0];
+        let e = elasticity(&x, &y, 0.9).unwrap();
+        assert_close(e.elasticity, 0.726_598_265_251_597_4, 1e-12);
+        assert_close(e.std_error, 0.024_071_013_148_176_25, 1e-10);
+        assert_close(e.ci_lo, 0.675_282_553_228_031_3, 1e-9);
+        assert_close(e.ci_hi, 0.777_913_977_275_163_5, 1e-9);
+        assert_close(e.p_value, 7.174_314_552_460_3e-6, 1e-12);
+        assert_close(e.r_squared, 0.995_629_232_976_068, 1e-12);
+        assert_eq!((e.n, e.level), (6, 0.9));
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        let ok = [1.0, 2.0, 3.0];
+        for bad in [[1.0, 0.0, 3.0], [1.0, -2.0, 3.0]] {
+            assert!(matches!(
+                elasticity(&bad, &ok, 0.95),
+                Err(ComputeError::NonPositive { arg: "x" })
+            ));
+            assert!(matches!(
+                elasticity(&ok, &bad, 0.95),
+                Err(ComputeError::NonPositive { arg: "y" })
+            ));
+        }
+        assert!(matches!(
+            elasticity(&[2.0; 3], &ok, 0.95),
+            Err(ComputeError::ZeroVariance { arg: "x" })
+        ));
+        for level in [0.0, 1.0] {
+            assert!(matches!(
+                elasticity(&ok, &ok, level),
+                Err(ComputeError::OutOfRange { arg: "level", .. })
+            ));
+        }
+    }
+}
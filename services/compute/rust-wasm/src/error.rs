@@ -0,0 +1,227 @@
+use std::fmt;
+
+use js_sys::{Error, Reflect};
+use wasm_bindgen::JsValue;
+
+/// Why a computation could not produce a value.
+///
+/// Every variant names the offending argument so callers can point at it.
+/// On the JS side this becomes an `Error` named `ComputeError` carrying
+/// `code` and `arg` properties alongside the message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComputeError {
+    /// The input has no values.
+    Empty { arg: &'static str },
+    /// The input has fewer values than the computation needs.
+    TooShort {
+        arg: &'static str,
+        min: usize,
+        len: usize,
+    },
+    /// Two inputs that must be paired have different lengths.
+    LengthMismatch {
+        arg: &'static str,
+        expected: usize,
+        len: usize,
+    },
+    /// Two year series share no years to pair values on.
+    NoOverlap { arg: &'static str },
+    /// Years are not evenly spaced, but the method needs a regular series.
+    Irregular { arg: &'static str },
+    /// Regressors are collinear, so the fit has no unique solution.
+    Singular { arg: &'static str },
+    /// The series moves the wrong way for the requested measure, e.g. a
+    /// doubling time for a declining series.
+    WrongDirection {
+        arg: &'static str,
+        expected: &'static str,
+    },
+    /// A value must be strictly positive (e.g. for a logarithm or ratio).
+    NonPositive { arg: &'static str },
+    /// A parameter lies outside the interval `[min, max]`.
+    OutOfRange {
+        arg: &'static str,
+        value: f64,
+        min: f64,
+        max: f64,
+    },
+    /// A relative measure was requested against a base of zero.
+    ZeroBase { arg: &'static str },
+    /// The input is constant, so a ratio involving its spread is undefined.
+    ZeroVariance { arg: &'static str },
+}
+
+impl ComputeError {
+    /// Stable machine-readable code, exposed to JS as `error.code`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ComputeError::Empty { .. } => "EMPTY_INPUT",
+            ComputeError::TooShort { .. } => "TOO_SHORT",
+            ComputeError::LengthMismatch { .. } => "LENGTH_MISMATCH",
+            ComputeError::NoOverlap { .. } => "NO_OVERLAP",
+            ComputeError::Irregular { .. } => "IRREGULAR_SPACING",
+            ComputeError::Singular { .. } => "SINGULAR",
+            ComputeError::WrongDirection { .. } => "WRONG_DIRECTION",
+            ComputeError::NonPositive { .. } => "NON_POSITIVE",
+            ComputeError::OutOfRange { .. } => "OUT_OF_RANGE",
+            ComputeError::ZeroBase { .. } => "ZERO_BASE",
+            ComputeError::ZeroVariance { .. } => "ZERO_VARIANCE",
+        }
+    }
+
+    /// Name of the argument the error refers to, exposed as `error.arg`.
+    pub fn arg(&self) -> &'static str {
+        match self {
+            ComputeError::Empty { arg }
+            | ComputeError::TooShort { arg, .. }
+            | ComputeError::LengthMismatch { arg, .. }
+            | ComputeError::NoOverlap { arg }
+            | ComputeError::Irregular { arg }
+            | ComputeError::Singular { arg }
+            | ComputeError::WrongDirection { arg, .. }
+            | ComputeError::NonPositive { arg }
+            | ComputeError::OutOfRange { arg, .. }
+            | ComputeError::ZeroBase { arg }
+            | ComputeError::ZeroVariance { arg } => arg,
+        }
+    }
+}
+
+impl fmt::Display for ComputeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComputeError::Empty { arg } => write!(f, "`{arg}` is empty"),
+            ComputeError::TooShort { arg, min, len } => {
+                write!(f, "`{arg}` needs at least {min} values, got {len}")
+            }
+            ComputeError::LengthMismatch { arg, expected, len } => {
+                write!(f, "`{arg}` has {len} values, expected {expected}")
+            }
+            ComputeError::NoOverlap { arg } => {
+                write!(f, "`{arg}` shares no years with the other series")
+            }
+            ComputeError::Irregular { arg } => {
+                write!(f, "`{arg}` must be evenly spaced with no missing years")
+            }
+            ComputeError::Singular { arg } => {
+                write!(
+                    f,
+                    "`{arg}` are collinear, so the fit has no unique solution"
+                )
+            }
+            ComputeError::WrongDirection { arg, expected } => {
+                write!(f, "`{arg}` must be {expected} for this measure")
+            }
+            ComputeError::NonPositive { arg } => {
+                write!(f, "`{arg}` must contain only positive values")
+            }
+            ComputeError::OutOfRange {
+                arg,
+                value,
+                min,
+                max,
+            } => write!(
+                f,
+                "`{arg}` is {value}, expected a value between {min} and {max}"
+            ),
+            ComputeError::ZeroBase { arg } => {
+                write!(
+                    f,
+                    "`{arg}` has a zero base, so the relative measure is undefined"
+                )
+            }
+            ComputeError::ZeroVariance { arg } => write!(f, "`{arg}` has zero variance"),
+        }
+    }
+}
+
+impl std::error::Error for ComputeError {}
+
+impl From<ComputeError> for JsValue {
+    fn from(err: ComputeError) -> JsValue {
+        let js = Error::new(&err.to_string());
+        js.set_name("ComputeError");
+        // Setting a property on a fresh Error object cannot fail.
+        let _ = Reflect::set(&js, &"code".into(), &err.code().into());
+        let _ = Reflect::set(&js, &"arg".into(), &err.arg().into());
+        js.into()
+    }
+}
+
+/// Check that `values` has at least `min` entries.
+pub(crate) fn require_len(
+    arg: &'static str,
+    values: &[f64],
+    min: usize,
+) -> Result<(), ComputeError> {
+    match values.len() {
+        0 => Err(ComputeError::Empty { arg }),
+        len if len < min => Err(ComputeError::TooShort { arg, min, len }),
+        _ => Ok(()),
+    }
+}
+
+/// Check that `values` pairs one-to-one with a slice of length `expected`.
+pub(crate) fn require_same_len(
+    arg: &'static str,
+    values: &[f64],
+    expected: usize,
+) -> Result<(), ComputeError> {
+    if values.len() == expected {
+        Ok(())
+    } else {
+        Err(ComputeError::LengthMismatch {
+            arg,
+            expected,
+            len: values.len(),
+        })
+    }
+}
+
+/// Check that `value` lies in `[min, max]`. NaN is always out of range.
+pub(crate) fn require_range(
+    arg: &'static str,
+    value: f64,
+    min: f64,
+    max: f64,
+) -> Result<(), ComputeError> {
+    if value >= min && value <= max {
+        Ok(())
+    } else {
+        Err(ComputeError::OutOfRange {
+            arg,
+            value,
+            min,
+            max,
+        })
+    }
+}
+
+/// Check that a confidence `level` lies strictly between 0 and 1; at
+/// either end the critical value is infinite.
+pub(crate) fn require_level(level: f64) -> Result<(), ComputeError> {
+    if level > 0.0 && level < 1.0 {
+        Ok(())
+    } else {
+        Err(ComputeError::OutOfRange {
+            arg: "level",
+            value: level,
+            min: 0.0,
+            max: 1.0,
+        })
+    }
+}
+
+/// Check that `years` are evenly spaced, so lags are well defined.
+pub(crate) fn require_regular(arg: &'static str, years: &[f64]) -> Result<(), ComputeError> {
+    if let [first, second, ..] = years {
+        let step = second - first;
+        let regular = years
+            .windows(2)
+            .all(|w| ((w[1] - w[0]) - step).abs() <= 1e-9 * step.abs().max(1.0));
+        if !regular {
+            return Err(ComputeError::Irregular { arg });
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,303 @@
+//! Exponential smoothing with additive errors: simple, Holt linear and
+//! damped trend.
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::criteria::{aic, aicc, bic, gaussian_log_lik};
+use crate::error::{require_len, require_range, require_regular, ComputeError};
+use crate::forecast::ForecastPoint;
+use crate::linalg::{self, Matrix};
+use crate::optimize::{logistic, logit, nelder_mead};
+use crate::series::YearSeries;
+
+/// Damping parameters are kept in this range, as in Hyndman et al.:
+/// below it the trend dies out almost at once, above it the model is
+/// indistinguishable from Holt's.
+const PHI_MIN: f64 = 0.8;
+const PHI_MAX: f64 = 0.98;
+
+/// Exponential smoothing model.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EtsModel {
+    /// Level only; forecasts are flat.
+    #[default]
+    Simple = 0,
+    /// Level and trend; forecasts continue the latest trend indefinitely.
+    Holt = 1,
+    /// Level and a trend that fades geometrically, so forecasts level
+    /// off. Suits indicators whose change is slowing down.
+    Damped = 2,
+}
+
+impl EtsModel {
+    fn has_trend(self) -> bool {
+        self != EtsModel::Simple
+    }
+
+    fn n_smoothing(self) -> usize {
+        match self {
+            EtsModel::Simple => 1,
+            EtsModel::Holt => 2,
+            EtsModel::Damped => 3,
+        }
+    }
+
+    /// Smoothing parameters plus initial states.
+    fn n_params(self) -> usize {
+        self.n_smoothing() + 1 + usize::from(self.has_trend())
+    }
+}
+
+/// A fitted exponential smoothing model and its forecasts.
+#[derive(Debug, Clone, Serialize)]
+pub struct EtsFit {
+    /// Level smoothing, in (0, 1).
+    pub alpha: f64,
+    /// Trend smoothing in error-correction form, in (0, alpha); `None`
+    /// without a trend.
+    pub beta: Option<f64>,
+    /// Trend damping; `None` unless damped.
+    pub phi: Option<f64>,
+    pub initial_level: f64,
+    pub initial_trend: Option<f64>,
+    pub sse: f64,
+    /// Residual standard deviation used for the intervals.
+    pub sigma: f64,
+    pub log_lik: f64,
+    pub aic: f64,
+    pub aicc: f64,
+    pub bic: f64,
+    /// One-step-ahead errors, in year order.
+    pub residuals: Vec<f64>,
+    pub n: usize,
+    pub forecast: Vec<ForecastPoint>,
+}
+
+/// Fit `model` to an evenly spaced series and forecast `horizon` steps.
+///
+/// Smoothing parameters are chosen by maximum likelihood, which for
+/// additive errors means least squares; for each candidate the initial
+/// level and trend are solved exactly, since the errors are linear in
+/// them. Information criteria count the variance as a parameter, so
+/// models fitted to the same series can be compared directly. Intervals
+/// use the analytical forecast variance under Gaussian errors.
+pub fn ets(series: &YearSeries, model: EtsModel, horizon: usize) -> Result<EtsFit, ComputeError> {
+    require_range("horizon", horizon as f64, 1.0, f64::INFINITY)?;
+    // AICc needs n > k + 1 with the variance counted in k.
+    require_len("values", series.values(), model.n_params() + 3)?;
+    require_regular("years", series.years())?;
+    let y = series.values();
+    let n = y.len();
+
+    let params = |u: &[f64]| -> Smoothing {
+        let alpha = logistic(u[0]);
+        match model {
+            EtsModel::Simple => Smoothing {
+                alpha,
+                beta: 0.0,
+                phi: 1.0,
+            },
+            EtsModel::Holt => Smoothing {
+                alpha,
+                beta: alpha * logistic(u[1]),
+                phi: 1.0,
+            },
+            EtsModel::Damped => Smoothing {
+                alpha,
+                beta: alpha * logistic(u[1]),
+                phi: PHI_MIN + (PHI_MAX - PHI_MIN) * logistic(u[2]),
+            },
+        }
+    };
+    let objective = |u: &[f64]| {
+        initial_states(y, params(u), model.has_trend()).map_or(f64::INFINITY, |(_, sse)| sse)
+    };
+
+    let mut best = (Vec::new(), f64::INFINITY);
+    for alpha in [0.2, 0.5, 0.8] {
+        let start = [logit(alpha), logit(0.1), 0.0];
+        let u = nelder_mead(objective, &start[..model.n_smoothing()], 0.5, 500);
+        let value = objective(&u);
+        if value < best.1 {
+            best = (u, value);
+        }
+    }
+    if !best.1.is_finite() {
+        return Err(ComputeError::Singular { arg: "values" });
+    }
+    let smoothing = params(&best.0);
+    let (states, sse) = initial_states(y, smoothing, model.has_trend())
+        .ok_or(ComputeError::Singular { arg: "values" })?;
+    let (residuals, level, trend) = run(y, smoothing, states);
+
+    let k = model.n_params() + 1;
+    let log_lik = gaussian_log_lik(sse, n);
+    let sigma = (sse / (n - model.n_params()) as f64).sqrt();
+
+    let years = series.years();
+    let step = years[1] - years[0];
+    let last = years[n - 1];
+    let mut damped_sum = 0.0;
+    let mut variance_sum = 0.0;
+    let forecast = (1..=horizon)
+        .map(|h| {
+            // The h-step error accumulates c_j = alpha + beta (phi + ... + phi^j)
+            // for j < h.
+            let c = smoothing.alpha + smoothing.beta * damped_sum;
+            if h > 1 {
+                variance_sum += c * c;
+            }
+            damped_sum += smoothing.phi.powi(h as i32);
+            ForecastPoint::new(
+                last + step * h as f64,
+                level + damped_sum * trend,
+                sigma * (1.0 + variance_sum).sqrt(),
+                None,
+            )
+        })
+        .collect();
+
+    Ok(EtsFit {
+        alpha: smoothing.alpha,
+        beta: model.has_trend().then_some(smoothing.beta),
+        phi: (model == EtsModel::Damped).then_some(smoothing.phi),
+        initial_level: states.0,
+        initial_trend: model.has_trend().then_some(states.1),
+        sse,
+        sigma,
+        log_lik,
+        aic: aic(log_lik, k),
+        aicc: aicc(log_lik, k, n),
+        bic: bic(log_lik, k, n),
+        residuals,
+        n,
+        forecast,
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Smoothing {
+    alpha: f64,
+    beta: f64,
+    phi: f64,
+}
+
+/// Run the error-correction recursions from initial `(level, trend)`,
+/// returning the one-step errors and the final level and trend.
+fn run(y: &[f64], s: Smoothing, (mut level, mut trend): (f64, f64)) -> (Vec<f64>, f64, f64) {
+    let residuals = y
+        .iter()
+        .map(|&v| {
+            let e = v - (level + s.phi * trend);
+            level += s.phi * trend + s.alpha * e;
+            trend = s.phi * trend + s.beta * e;
+            e
+        })
+        .collect();
+    (residuals, level, trend)
+}
+
+/// Least-squares initial level and trend for the given smoothing
+/// parameters, with the resulting sum of squared errors.
+fn initial_states(y: &[f64], s: Smoothing, trend: bool) -> Option<((f64, f64), f64)> {
+    // Errors are affine in the initial states: e = e0 - D theta, where the
+    // columns of D are the responses to a unit level and a unit trend.
+    let (e0, _, _) = run(y, s, (0.0, 0.0));
+    let (e_level, _, _) = run(y, s, (1.0, 0.0));
+    let mut columns = vec![e0
+        .iter()
+        .zip(&e_level)
+        .map(|(a, b)| a - b)
+        .collect::<Vec<f64>>()];
+    if trend {
+        let (e_trend, _, _) = run(y, s, (0.0, 1.0));
+        columns.push(e0.iter().zip(&e_trend).map(|(a, b)| a - b).collect());
+    }
+    let refs: Vec<&[f64]> = columns.iter().map(Vec::as_slice).collect();
+    let fit = linalg::lstsq(&Matrix::from_columns(y.len(), &refs, false), &e0)?;
+    let theta = (fit.coef[0], fit.coef.get(1).copied().unwrap_or(0.0));
+    Some((theta, fit.sse))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dist;
+    use crate::testing::{annual, assert_close, LH};
+
+    /// Half-width of the 95% interval as a multiple of sigma.
+    fn widths(fit: &EtsFit) -> Vec<f64> {
+        let z = dist::normal_quantile(0.975);
+        fit.forecast
+            .iter()
+            .map(|p| (p.hi95 - p.point) / (z * fit.sigma))
+            .collect()
+    }
+
+    #[test]
+    fn simple_smoothing_recursion_and_intervals() {
+        let fit = ets(&annual(&LH), EtsModel::Simple, 4).unwrap();
+        let alpha = fit.alpha;
+        assert!(alpha > 0.0 && alpha < 1.0);
+        assert_eq!((fit.beta, fit.phi, fit.initial_trend), (None, None, None));
+
+        // Replay l_t = l_{t-1} + alpha e_t from the reported initial level.
+        let mut level = fit.initial_level;
+        for (y, e) in LH.iter().zip(&fit.residuals) {
+            assert_close(*e, y - level, 1e-12);
+            level += alpha * e;
+        }
+        assert_close(fit.sse, fit.residuals.iter().map(|e| e * e).sum(), 1e-12);
+        assert_close(fit.sigma, (fit.sse / (LH.len() - 2) as f64).sqrt(), 1e-12);
+
+        // Hyndman & Athanasopoulos, FPP: sigma_h² = sigma²(1 + (h-1) alpha²).
+        for (h, width) in widths(&fit).iter().enumerate() {
+            assert_close(fit.forecast[h].point, level, 1e-12);
+            assert_close(*width, (1.0 + h as f64 * alpha * alpha).sqrt(), 1e-12);
+        }
+        assert_close(fit.forecast[0].year, 2018.0, 1e-12);
+    }
+
+    #[test]
+    fn trend_model_intervals() {
+        let holt = ets(&annual(&LH), EtsModel::Holt, 5).unwrap();
+        let (alpha, beta) = (holt.alpha, holt.beta.unwrap());
+        for (h, width) in widths(&holt).iter().enumerate() {
+            let sum: f64 = (1..=h).map(|j| (alpha + beta * j as f64).powi(2)).sum();
+            assert_close(*width, (1.0 + sum).sqrt(), 1e-12);
+        }
+
+        let damped = ets(&annual(&LH), EtsModel::Damped, 5).unwrap();
+        let (alpha, beta, phi) = (damped.alpha, damped.beta.unwrap(), damped.phi.unwrap());
+        assert!((PHI_MIN..=PHI_MAX).contains(&phi));
+        for (h, width) in widths(&damped).iter().enumerate() {
+            let sum: f64 = (1..=h)
+                .map(|j| {
+                    let damping: f64 = (1..=j).map(|i| phi.powi(i as i32)).sum();
+                    (alpha + beta * damping).powi(2)
+                })
+                .sum();
+            assert_close(*width, (1.0 + sum).sqrt(), 1e-12);
+        }
+    }
+
+    #[test]
+    fn holt_extends_an_exact_line() {
+        let line: Vec<f64> = (0..12).map(|t| 5.0 + 1.5 * t as f64).collect();
+        let fit = ets(&annual(&line), EtsModel::Holt, 3).unwrap();
+        assert!(fit.sse < 1e-12);
+        for (h, p) in fit.forecast.iter().enumerate() {
+            assert_close(p.point, 5.0 + 1.5 * (12 + h) as f64, 1e-6);
+        }
+    }
+
+    #[test]
+    fn criteria_count_every_parameter() {
+        let fit = ets(&annual(&LH), EtsModel::Holt, 1).unwrap();
+        // alpha, beta, two initial states and the variance.
+        assert_close(fit.aic, -2.0 * fit.log_lik + 10.0, 1e-12);
+        assert_close(fit.aicc, fit.aic + 60.0 / 42.0, 1e-12);
+    }
+}
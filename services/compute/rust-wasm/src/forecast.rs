@@ -0,0 +1,124 @@
+//! Forecasts with prediction intervals, shaped for charting.
+
+use serde::Serialize;
+
+use crate::dist;
+use crate::error::{require_len, require_range, ComputeError};
+use crate::regression;
+use crate::series::YearSeries;
+
+/// One forecast year with its 80% and 95% prediction intervals.
+#[derive(Debug, Clone, Serialize)]
+pub struct ForecastPoint {
+    pub year: f64,
+    pub point: f64,
+    pub lo80: f64,
+    pub hi80: f64,
+    pub lo95: f64,
+    pub hi95: f64,
+}
+
+impl ForecastPoint {
+    /// Symmetric intervals of `point ± q * se`, with `q` from Student's t
+    /// on `df` degrees of freedom, or the normal if `df` is `None`.
+    pub(crate) fn new(year: f64, point: f64, se: f64, df: Option<f64>) -> Self {
+        let quantile = |p: f64| match df {
+            Some(df) => dist::student_t_quantile(p, df),
+            None => dist::normal_quantile(p),
+        };
+        let (q80, q95) = (quantile(0.9), quantile(0.975));
+        ForecastPoint {
+            year,
+            point,
+            lo80: point - q80 * se,
+            hi80: point + q80 * se,
+            lo95: point - q95 * se,
+            hi95: point + q95 * se,
+        }
+    }
+}
+
+/// Extend the year-aware linear trend `horizon` steps past the last
+/// observation, where a step is the series' spacing (see `step`).
+///
+/// Points come from the fitted line, not the last observation, and the
+/// intervals cover both the noise around the line and the uncertainty in
+/// its slope and level, so they widen with distance from the data.
+pub fn linear_forecast(
+    series: &YearSeries,
+    horizon: usize,
+) -> Result<Vec<ForecastPoint>, ComputeError> {
+    require_range("horizon", horizon as f64, 1.0, f64::INFINITY)?;
+    require_len("values", series.values(), 3)?;
+    let years = series.years();
+    let fit = regression::linear_fit(years, series.values())?;
+
+    let n = fit.n as f64;
+    let year_mean = years.iter().sum::<f64>() / n;
+    let sxx: f64 = years.iter().map(|y| (y - year_mean).powi(2)).sum();
+    let sigma = (fit.residuals.iter().map(|r| r * r).sum::<f64>() / (n - 2.0)).sqrt();
+    let step = step(years);
+    let last = years[years.len() - 1];
+    Ok((1..=horizon)
+        .map(|h| {
+            let year = last + step * h as f64;
+            let se = sigma * (1.0 + 1.0 / n + (year - year_mean).powi(2) / sxx).sqrt();
+            ForecastPoint::new(year, fit.predict(year), se, Some(n - 2.0))
+        })
+        .collect())
+}
+
+/// Spacing between forecast years: the smallest gap between consecutive
+/// years, so quarterly data steps by 0.25 and annual data with missing
+/// years still steps by one. Equals `years[1] - years[0]` on an evenly
+/// spaced series; 1 for fewer than two years.
+pub(crate) fn step(years: &[f64]) -> f64 {
+    years
+        .windows(2)
+        .map(|w| w[1] - w[0])
+        .min_by(f64::total_cmp)
+        .unwrap_or(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    #[test]
+    fn linear_intervals_use_t_quantiles() {
+        let series = YearSeries::new(
+            &[2000.0, 2001.0, 2002.0, 2003.0, 2004.0],
+            &[1.0, 3.0, 2.0, 5.0, 4.0],
+        );
+        let forecast = linear_forecast(&series, 2).unwrap();
+        assert_close(forecast[0].year, 2005.0, 1e-9);
+        assert_close(forecast[0].point, 5.4, 1e-9);
+        assert_close(forecast[0].lo80, 2.800161437411702, 1e-9);
+        assert_close(forecast[0].hi95, 10.451976890758836, 1e-9);
+        assert_close(forecast[1].point, 6.2, 1e-9);
+        assert_close(forecast[1].lo80, 3.197965012080356, 1e-9);
+        assert_close(forecast[1].hi95, 12.03352043563897, 1e-9);
+    }
+
+    #[test]
+    fn linear_steps_by_series_spacing() {
+        let years = [2000.0, 2005.0, 2010.0, 2015.0];
+        let series = YearSeries::new(&years, &[1.0, 2.0, 2.5, 4.0]);
+        let forecast = linear_forecast(&series, 2).unwrap();
+        assert_close(forecast[0].year, 2020.0, 1e-9);
+        assert_close(forecast[1].year, 2025.0, 1e-9);
+
+        let quarters: Vec<f64> = (0..8).map(|q| 2020.0 + 0.25 * q as f64).collect();
+        let values: Vec<f64> = (0..8).map(|q| q as f64).collect();
+        let forecast = linear_forecast(&YearSeries::new(&quarters, &values), 1).unwrap();
+        assert_close(forecast[0].year, 2022.0, 1e-9);
+        assert_close(forecast[0].point, 8.0, 1e-9);
+    }
+
+    #[test]
+    fn gaps_do_not_stretch_the_step() {
+        assert_close(step(&[1990.0, 1991.0, 1995.0, 1996.0]), 1.0, 1e-9);
+        assert_close(step(&[2000.0]), 1.0, 1e-9);
+    }
+}
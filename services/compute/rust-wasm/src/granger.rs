@@ -0,0 +1,217 @@
+use serde::Serialize;
+
+use crate::align::Aligned;
+use crate::criteria::InformationCriterion;
+use crate::dist;
+use crate::error::{require_len, require_range, require_regular, ComputeError};
+use crate::linalg::{self, Matrix};
+
+/// F test of whether one series' past improves prediction of the other.
+#[derive(Debug, Clone, Serialize)]
+pub struct GrangerDirection {
+    /// F statistic comparing the restricted model (own lags only) with the
+    /// unrestricted one (own and other lags).
+    pub f_stat: f64,
+    /// Numerator degrees of freedom: the lag order.
+    pub df1: f64,
+    /// Denominator degrees of freedom: `n - 2 * lag_order - 1`.
+    pub df2: f64,
+    pub p_value: f64,
+}
+
+/// Granger causality tests in both directions at one lag order.
+#[derive(Debug, Clone, Serialize)]
+pub struct GrangerResult {
+    /// Lag order used for both tests.
+    pub lag_order: usize,
+    /// Criterion value of the bivariate VAR at each lag 1..=max_lag, on a
+    /// common sample; `lag_order` is the minimizer.
+    pub criterion_values: Vec<f64>,
+    /// Observations entering each test regression.
+    pub n: usize,
+    /// Does the past of `a` help predict `b`?
+    pub a_causes_b: GrangerDirection,
+    /// Does the past of `b` help predict `a`?
+    pub b_causes_a: GrangerDirection,
+}
+
+/// Granger causality between two aligned, evenly spaced series.
+///
+/// The lag order is chosen by `criterion` over a bivariate VAR with
+/// intercept for every order up to `max_lag`, fitted on the same
+/// observations so the values are comparable. Both directions are then
+/// tested at that order using all available observations.
+///
+/// Needs at least `3 * max_lag + 2` points, so the unrestricted regression
+/// at `max_lag` keeps a residual degree of freedom.
+pub fn granger(
+    aligned: &Aligned,
+    max_lag: usize,
+    criterion: InformationCriterion,
+) -> Result<GrangerResult, ComputeError> {
+    require_range("max_lag", max_lag as f64, 1.0, f64::INFINITY)?;
+    require_len("values", &aligned.a, 3 * max_lag + 2)?;
+    require_regular("years", &aligned.years)?;
+    let (a, b) = (&aligned.a, &aligned.b);
+
+    let criterion_values = (1..=max_lag)
+        .map(|p| {
+            let ea = lag_regression(a, b, p, max_lag, true)?;
+            let eb = lag_regression(b, a, p, max_lag, true)?;
+            let t = ea.residuals.len();
+            let cov =
+                |x: &[f64], y: &[f64]| x.iter().zip(y).map(|(x, y)| x * y).sum::<f64>() / t as f64;
+            let det = cov(&ea.residuals, &ea.residuals) * cov(&eb.residuals, &eb.residuals)
+                - cov(&ea.residuals, &eb.residuals).powi(2);
+            let log_lik =
+                -0.5 * t as f64 * (2.0 * (2.0 * std::f64::consts::PI).ln() + det.ln() + 2.0);
+            Ok(criterion.value(log_lik, 2 * (1 + 2 * p), t))
+        })
+        .collect::<Result<Vec<f64>, ComputeError>>()?;
+    let lag_order = criterion_values
+        .iter()
+        .enumerate()
+        .min_by(|x, y| x.1.total_cmp(y.1))
+        .map_or(1, |(i, _)| i + 1);
+
+    Ok(GrangerResult {
+        lag_order,
+        criterion_values,
+        n: aligned.len() - lag_order,
+        a_causes_b: direction(b, a, lag_order)?,
+        b_causes_a: direction(a, b, lag_order)?,
+    })
+}
+
+/// Test whether lags of `cause` help predict `target`.
+fn direction(target: &[f64], cause: &[f64], p: usize) -> Result<GrangerDirection, ComputeError> {
+    let unrestricted = lag_regression(target, cause, p, p, true)?;
+    let restricted = lag_regression(target, cause, p, p, false)?;
+    let t = unrestricted.residuals.len();
+    let df1 = p as f64;
+    let df2 = (t - 2 * p - 1) as f64;
+    let f_stat = if unrestricted.sse == 0.0 {
+        f64::INFINITY
+    } else {
+        ((restricted.sse - unrestricted.sse) / df1) / (unrestricted.sse / df2)
+    };
+    Ok(GrangerDirection {
+        f_stat,
+        df1,
+        df2,
+        p_value: dist::f_sf(f_stat, df1, df2),
+    })
+}
+
+/// Regress `target[t]` on an intercept and `p` lags of `target` (plus `p`
+/// lags of `other` if `with_other`), for `t` from `start` on.
+fn lag_regression(
+    target: &[f64],
+    other: &[f64],
+    p: usize,
+    start: usize,
+    with_other: bool,
+) -> Result<linalg::LeastSquares, ComputeError> {
+    let rows = target.len() - start;
+    let cols = 1 + p * if with_other { 2 } else { 1 };
+    let mut x = Matrix::zeros(rows, cols);
+    for (row, t) in (start..target.len()).enumerate() {
+        x[(row, 0)] = 1.0;
+        for lag in 1..=p {
+            x[(row, lag)] = target[t - lag];
+            if with_other {
+                x[(row, p + lag)] = other[t - lag];
+            }
+        }
+    }
+    linalg::lstsq(&x, &target[start..]).ok_or(ComputeError::Singular { arg: "values" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::align::{align, Join};
+    use crate::testing::{annual, assert_close};
+
+    const A: [f64; 20] = [
+        3.0, 7.0, 4.0, 8.0, 2.0, 6.0, 9.0, 5.0, 1.0, 4.0, 8.0, 3.0, 7.0, 6.0, 2.0, 9.0, 5.0, 4.0,
+        8.0, 3.0,
+    ];
+    // Roughly 0.8 times `A` a year earlier, plus noise.
+    const B: [f64; 20] = [
+        5.0, 2.0, 7.0, 7.0, 7.0, 4.0, 6.0, 8.0, 8.0, 3.0, 3.0, 8.0, 5.0, 6.0, 8.0, 3.0, 7.0, 7.0,
+        5.0, 7.0,
+    ];
+
+    fn pair(a: &[f64], b: &[f64]) -> Aligned {
+        align(&annual(a), &annual(b), Join::Inner)
+    }
+
+    #[test]
+    fn f_tests_match_ssr_based_reference() {
+        // statsmodels' grangercausalitytests ssr_based_F_test, which fits
+        // the same restricted and unrestricted regressions.
+        let result = granger(&pair(&A, &B), 2, InformationCriterion::Aic).unwrap();
+        assert_eq!((result.lag_order, result.n), (2, 18));
+        let ab = &result.a_causes_b;
+        assert_eq!((ab.df1, ab.df2), (2.0, 13.0));
+        assert_close(ab.f_stat, 33.719_411_357_188_03, 1e-10);
+        assert_close(ab.p_value, 7.163_160_838_979_133e-6, 1e-8);
+        let ba = &result.b_causes_a;
+        assert_close(ba.f_stat, 1.498_996_980_102_714_8, 1e-10);
+        assert_close(ba.p_value, 0.259_540_928_992_312_6, 1e-10);
+    }
+
+    #[test]
+    fn lag_order_minimizes_the_criterion_on_a_common_sample() {
+        let aic = granger(&pair(&A, &B), 3, InformationCriterion::Aic).unwrap();
+        let expected = [
+            129.745_285_504_144_08,
+            116.107_175_594_082_34,
+            109.937_608_712_573_95,
+        ];
+        for (v, e) in aic.criterion_values.iter().zip(expected) {
+            assert_close(*v, e, 1e-10);
+        }
+        let bic = granger(&pair(&A, &B), 3, InformationCriterion::Bic).unwrap();
+        let expected = [
+            134.744_565_568_481_37,
+            124.439_309_034_644_5,
+            121.602_595_529_360_97,
+        ];
+        for (v, e) in bic.criterion_values.iter().zip(expected) {
+            assert_close(*v, e, 1e-10);
+        }
+        assert_eq!((bic.lag_order, bic.n), (3, 17));
+        assert_close(bic.a_causes_b.f_stat, 26.976_610_354_547_66, 1e-10);
+        assert_close(bic.a_causes_b.p_value, 4.150_241_197_311_520_4e-5, 1e-8);
+        assert_eq!(bic.b_causes_a.df2, 10.0);
+        assert_close(bic.b_causes_a.p_value, 0.369_506_277_953_285, 1e-10);
+    }
+
+    #[test]
+    fn rejects_short_irregular_and_collinear_input() {
+        assert_eq!(
+            granger(&pair(&A[..10], &B[..10]), 3, InformationCriterion::Aic).unwrap_err(),
+            ComputeError::TooShort {
+                arg: "values",
+                min: 11,
+                len: 10
+            }
+        );
+
+        let mut gappy = pair(&A, &B);
+        gappy.years[10] += 0.5;
+        assert_eq!(
+            granger(&gappy, 2, InformationCriterion::Aic).unwrap_err(),
+            ComputeError::Irregular { arg: "years" }
+        );
+
+        // Lags of a straight line are collinear with the intercept.
+        let line: Vec<f64> = (0..20).map(|t| 10.0 + 0.5 * t as f64).collect();
+        assert_eq!(
+            granger(&pair(&line, &B), 2, InformationCriterion::Aic).unwrap_err(),
+            ComputeError::Singular { arg: "values" }
+        );
+    }
+}
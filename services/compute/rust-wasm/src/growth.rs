@@ -0,0 +1,111 @@
+use std::f64::consts::LN_2;
+
+use crate::change;
+use crate::error::{require_len, ComputeError};
+use crate::series::YearSeries;
+
+/// Compound annual growth rate in percent per year between the first and
+/// last observation.
+///
+/// The exponent is the span between the first and last *year*, so gaps in
+/// the series are accounted for. Zero or negative endpoints have no
+/// compound growth rate and are rejected with `NonPositive`.
+pub fn cagr(series: &YearSeries) -> Result<f64, ComputeError> {
+    let values = series.values();
+    require_len("values", values, 2)?;
+    let years = series.years();
+    change::cagr(
+        values[0],
+        values[values.len() - 1],
+        years[years.len() - 1] - years[0],
+    )
+}
+
+/// Years needed to double at the series' compound annual growth rate.
+pub fn doubling_time(series: &YearSeries) -> Result<f64, ComputeError> {
+    let rate = cagr(series)? / 100.0;
+    if rate <= 0.0 {
+        return Err(ComputeError::WrongDirection {
+            arg: "values",
+            expected: "increasing",
+        });
+    }
+    Ok(LN_2 / rate.ln_1p())
+}
+
+/// Years needed to halve at the series' compound annual rate of decline.
+pub fn halving_time(series: &YearSeries) -> Result<f64, ComputeError> {
+    let rate = cagr(series)? / 100.0;
+    if rate >= 0.0 {
+        return Err(ComputeError::WrongDirection {
+            arg: "values",
+            expected: "decreasing",
+        });
+    }
+    Ok(LN_2 / -rate.ln_1p())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    fn gappy(values: &[f64]) -> YearSeries {
+        YearSeries::new(&[2001.0, 2004.0, 2013.0], values)
+    }
+
+    #[test]
+    fn cagr_spans_the_gaps() {
+        // Doubles over the twelve years from 2001 to 2013.
+        let series = gappy(&[100.0, 150.0, 200.0]);
+        assert_close(cagr(&series).unwrap(), 5.946_309_435_929_526, 1e-12);
+        assert_close(doubling_time(&series).unwrap(), 12.0, 1e-12);
+    }
+
+    #[test]
+    fn halving_time_of_a_decline() {
+        // Quarters over twelve years, so halves every six.
+        let series = gappy(&[200.0, 120.0, 50.0]);
+        assert_close(halving_time(&series).unwrap(), 6.0, 1e-12);
+        assert_eq!(
+            doubling_time(&series),
+            Err(ComputeError::WrongDirection {
+                arg: "values",
+                expected: "increasing"
+            })
+        );
+    }
+
+    #[test]
+    fn flat_series_neither_doubles_nor_halves() {
+        let series = gappy(&[5.0, 7.0, 5.0]);
+        assert_eq!(cagr(&series), Ok(0.0));
+        assert!(doubling_time(&series).is_err());
+        assert_eq!(
+            halving_time(&series),
+            Err(ComputeError::WrongDirection {
+                arg: "values",
+                expected: "decreasing"
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_non_positive_endpoints() {
+        for values in [[0.0, 1.0, 2.0], [3.0, 1.0, 0.0], [-2.0, 1.0, 4.0]] {
+            let series = gappy(&values);
+            let err = Err(ComputeError::NonPositive { arg: "values" });
+            assert_eq!(cagr(&series), err);
+            assert_eq!(doubling_time(&series), err);
+            assert_eq!(halving_time(&series), err);
+        }
+        assert_eq!(
+            cagr(&YearSeries::new(&[2001.0], &[1.0])),
+            Err(ComputeError::TooShort {
+                arg: "values",
+                min: 2,
+                len: 1
+            })
+        );
+    }
+}
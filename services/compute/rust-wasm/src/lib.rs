@@ -1,111 +1,982 @@
 use wasm_bindgen::prelude::*;
-use js_sys::Float64Array;
+use js_sys::{Float64Array, Uint32Array};
+use serde::Serialize;
+
+pub mod align;
+pub mod arima;
+pub mod backtest;
+pub mod bounded;
+pub mod change;
+pub mod correlation;
+pub mod criteria;
+pub mod dist;
+pub mod elasticity;
+pub mod error;
+pub mod ets;
+pub mod forecast;
+pub mod granger;
+pub mod growth;
+pub mod linalg;
+pub mod multiple;
+pub mod ols;
+pub mod optimize;
+pub mod panel;
+pub mod regression;
+pub mod rolling;
+pub mod series;
+pub mod stationarity;
+pub mod stats;
+pub mod summary;
+#[cfg(test)]
+mod testing;
+
+pub use align::Join;
+pub use backtest::ForecastMethod;
+pub use bounded::GrowthCurve;
+pub use change::ChangeMeasure;
+pub use correlation::CorrelationMethod;
+pub use criteria::InformationCriterion;
+pub use error::ComputeError;
+pub use ets::EtsModel;
+pub use multiple::PAdjustMethod;
+pub use panel::PanelModel;
+pub use series::YearSeries;
+pub use stationarity::Deterministic;
+pub use stats::QuantileMethod;
+
+/// Serialize a result struct into a plain JS object. `None` fields become
+/// `null` so results survive a round trip through JSON.
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    let serializer = serde_wasm_bindgen::Serializer::new().serialize_missing_as_null(true);
+    value.serialize(&serializer).map_err(JsValue::from)
+}
+
+/// Read a JS year/value array pair into a sorted `YearSeries`.
+fn year_series(years: &Float64Array, values: &Float64Array) -> Result<YearSeries, ComputeError> {
+    YearSeries::from_arrays("years", &years.to_vec(), &values.to_vec())
+}
+
+// The original exports keep their NaN-on-failure contract; each has a
+// `try_` twin that throws a `ComputeError` (with `code` and `arg`) instead.
+// Everything added since throws directly.
 
 /// Compute the arithmetic mean of values.
 /// Returns NaN if array is empty.
 #[wasm_bindgen]
 pub fn mean(values: &Float64Array) -> f64 {
-    let len = values.length() as usize;
-    if len == 0 {
-        return f64::NAN;
-    }
-    let mut sum = 0.0;
-    for i in 0..len {
-        sum += values.get_index(i as u32);
-    }
-    sum / (len as f64)
+    try_mean(values).unwrap_or(f64::NAN)
+}
+
+/// Compute the arithmetic mean of values.
+/// Throws `EMPTY_INPUT` if array is empty.
+#[wasm_bindgen]
+pub fn try_mean(values: &Float64Array) -> Result<f64, JsValue> {
+    Ok(stats::mean(&values.to_vec())?)
 }
 
 /// Compute percent change between first and last value.
 /// Returns NaN if fewer than 2 values, or if first == 0.
 #[wasm_bindgen]
 pub fn pct_change(values: &Float64Array) -> f64 {
-    let len = values.length() as usize;
-    if len < 2 {
-        return f64::NAN;
-    }
-    let first = values.get_index(0);
-    let last = values.get_index((len - 1) as u32);
-    if first == 0.0 {
-        return f64::NAN;
-    }
-    ((last - first) / first.abs()) * 100.0
+    try_pct_change(values).unwrap_or(f64::NAN)
+}
+
+/// Compute percent change between first and last value.
+/// Throws `TOO_SHORT` if fewer than 2 values, `ZERO_BASE` if first == 0.
+#[wasm_bindgen]
+pub fn try_pct_change(values: &Float64Array) -> Result<f64, JsValue> {
+    Ok(change::pct_change(&values.to_vec())?)
 }
 
 /// Compute slope (linear trend) of values across index positions.
 /// Returns NaN if fewer than 2 values.
 #[wasm_bindgen]
 pub fn slope(values: &Float64Array) -> f64 {
-    let n = values.length() as usize;
-    if n < 2 {
-        return f64::NAN;
-    }
+    try_slope(values).unwrap_or(f64::NAN)
+}
 
-    let n_f = n as f64;
-    let x_mean = (n_f - 1.0) / 2.0;
+/// Compute slope (linear trend) of values across index positions.
+/// Throws `TOO_SHORT` if fewer than 2 values.
+#[wasm_bindgen]
+pub fn try_slope(values: &Float64Array) -> Result<f64, JsValue> {
+    Ok(regression::slope(&values.to_vec())?)
+}
 
-    // mean of y
-    let mut y_sum = 0.0;
-    for i in 0..n {
-        y_sum += values.get_index(i as u32);
-    }
-    let y_mean = y_sum / n_f;
-
-    let mut num = 0.0;
-    let mut den = 0.0;
-    for i in 0..n {
-        let x = i as f64;
-        let dx = x - x_mean;
-        let dy = values.get_index(i as u32) - y_mean;
-        num += dx * dy;
-        den += dx * dx;
-    }
+/// Compute slope (linear trend) of values against their actual years,
+/// so gaps in the series are respected. Returns NaN if fewer than 2
+/// points or if all years are equal.
+#[wasm_bindgen]
+pub fn slope_xy(years: &Float64Array, values: &Float64Array) -> f64 {
+    try_slope_xy(years, values).unwrap_or(f64::NAN)
+}
 
-    if den == 0.0 {
-        f64::NAN
-    } else {
-        num / den
-    }
+/// Compute slope (linear trend) of values against their actual years.
+/// Throws `TOO_SHORT`, `LENGTH_MISMATCH` or `ZERO_VARIANCE` (all years equal).
+#[wasm_bindgen]
+pub fn try_slope_xy(years: &Float64Array, values: &Float64Array) -> Result<f64, JsValue> {
+    Ok(regression::linear_fit(&years.to_vec(), &values.to_vec())?.slope)
+}
+
+/// Fit a year-aware linear trend. Returns an object with `slope`,
+/// `intercept`, `r_squared`, `slope_std_error`, `residuals` and `n`.
+/// Throws under the same conditions as `try_slope_xy`.
+#[wasm_bindgen]
+pub fn linear_fit(years: &Float64Array, values: &Float64Array) -> Result<JsValue, JsValue> {
+    to_js(&regression::linear_fit(&years.to_vec(), &values.to_vec())?)
 }
 
 /// Compute Pearson correlation coefficient between two equal-length arrays.
+/// Values are paired by position; use `pearson_years` when the series may
+/// cover different years.
 /// Returns NaN if arrays are empty or denominator is zero.
 #[wasm_bindgen]
 pub fn pearson(a: &Float64Array, b: &Float64Array) -> f64 {
-    let na = a.length() as usize;
-    let nb = b.length() as usize;
-    let n = std::cmp::min(na, nb);
-    if n == 0 {
-        return f64::NAN;
+    let (a, b) = (a.to_vec(), b.to_vec());
+    let n = a.len().min(b.len());
+    correlation::pearson(&a[..n], &b[..n]).unwrap_or(f64::NAN)
+}
+
+/// Compute Pearson correlation coefficient between two equal-length arrays.
+/// Throws `EMPTY_INPUT`, `LENGTH_MISMATCH` or `ZERO_VARIANCE`.
+#[wasm_bindgen]
+pub fn try_pearson(a: &Float64Array, b: &Float64Array) -> Result<f64, JsValue> {
+    Ok(correlation::pearson(&a.to_vec(), &b.to_vec())?)
+}
+
+/// Compute Pearson correlation between two year series, pairing values by
+/// year according to `join`. Years where either side is still missing
+/// after the join are dropped. Returns NaN if no years overlap or the
+/// denominator is zero.
+#[wasm_bindgen]
+pub fn pearson_years(
+    years_a: &Float64Array,
+    values_a: &Float64Array,
+    years_b: &Float64Array,
+    values_b: &Float64Array,
+    join: Join,
+) -> f64 {
+    try_pearson_years(years_a, values_a, years_b, values_b, join).unwrap_or(f64::NAN)
+}
+
+/// Compute Pearson correlation between two year series.
+/// Throws `NO_OVERLAP` if no years pair up, otherwise as `try_pearson`.
+#[wasm_bindgen]
+pub fn try_pearson_years(
+    years_a: &Float64Array,
+    values_a: &Float64Array,
+    years_b: &Float64Array,
+    values_b: &Float64Array,
+    join: Join,
+) -> Result<f64, JsValue> {
+    let aligned = align_complete(years_a, values_a, years_b, values_b, join)?;
+    Ok(correlation::pearson(&aligned.a, &aligned.b)?)
+}
+
+/// Align two year series on a common year axis. Returns an object with
+/// `years`, `a` and `b` arrays; missing values are NaN.
+/// Throws `LENGTH_MISMATCH` if a series' years and values differ in length.
+#[wasm_bindgen]
+pub fn align_series(
+    years_a: &Float64Array,
+    values_a: &Float64Array,
+    years_b: &Float64Array,
+    values_b: &Float64Array,
+    join: Join,
+) -> Result<JsValue, JsValue> {
+    to_js(&align_pair(years_a, values_a, years_b, values_b, join)?)
+}
+
+/// Align two series and keep only years where both have a value.
+fn align_complete(
+    years_a: &Float64Array,
+    values_a: &Float64Array,
+    years_b: &Float64Array,
+    values_b: &Float64Array,
+    join: Join,
+) -> Result<align::Aligned, ComputeError> {
+    let aligned = align_pair(years_a, values_a, years_b, values_b, join)?.complete();
+    if aligned.is_empty() {
+        return Err(ComputeError::NoOverlap { arg: "years_b" });
     }
+    Ok(aligned)
+}
+
+fn align_pair(
+    years_a: &Float64Array,
+    values_a: &Float64Array,
+    years_b: &Float64Array,
+    values_b: &Float64Array,
+    join: Join,
+) -> Result<align::Aligned, ComputeError> {
+    let a = YearSeries::from_arrays("years_a", &years_a.to_vec(), &values_a.to_vec())?;
+    let b = YearSeries::from_arrays("years_b", &years_b.to_vec(), &values_b.to_vec())?;
+    Ok(align::align(&a, &b, join))
+}
 
-    let n_f = n as f64;
-    let mut mean_a = 0.0;
-    let mut mean_b = 0.0;
+/// Compute every headline statistic for one year series in a single call.
+/// Returns an object with `count`, `mean`, `median`, `min`, `max`, `first`,
+/// `last`, `first_year`, `last_year`, `pct_change`, `slope`, `cagr` and
+/// `std_dev`; statistics that do not apply are `null`.
+/// Throws `LENGTH_MISMATCH` if years and values differ in length.
+#[wasm_bindgen]
+pub fn summarize(years: &Float64Array, values: &Float64Array) -> Result<JsValue, JsValue> {
+    let series = year_series(years, values)?;
+    to_js(&summary::summarize(&series))
+}
+
+/// Summarize many year series at once. `years` and `values` hold every
+/// series back to back and `lengths[i]` is the number of points in series
+/// `i`. Returns an array of summaries in the same order as `lengths`.
+/// Throws `LENGTH_MISMATCH` if the lengths do not add up to the input.
+#[wasm_bindgen]
+pub fn summarize_batch(
+    years: &Float64Array,
+    values: &Float64Array,
+    lengths: &Uint32Array,
+) -> Result<JsValue, JsValue> {
+    let summaries = summary::summarize_batch(&years.to_vec(), &values.to_vec(), &lengths.to_vec())?;
+    to_js(&summaries)
+}
+
+/// Sample variance of values (n - 1 denominator).
+/// Throws `TOO_SHORT` if fewer than 2 values.
+#[wasm_bindgen]
+pub fn variance(values: &Float64Array) -> Result<f64, JsValue> {
+    Ok(stats::variance(&values.to_vec())?)
+}
+
+/// Population variance of values (n denominator).
+/// Throws `EMPTY_INPUT` if array is empty.
+#[wasm_bindgen]
+pub fn population_variance(values: &Float64Array) -> Result<f64, JsValue> {
+    Ok(stats::population_variance(&values.to_vec())?)
+}
+
+/// Sample standard deviation of values (n - 1 denominator).
+/// Throws `TOO_SHORT` if fewer than 2 values.
+#[wasm_bindgen]
+pub fn std_dev(values: &Float64Array) -> Result<f64, JsValue> {
+    Ok(stats::std_dev(&values.to_vec())?)
+}
+
+/// Population standard deviation of values (n denominator).
+/// Throws `EMPTY_INPUT` if array is empty.
+#[wasm_bindgen]
+pub fn population_std_dev(values: &Float64Array) -> Result<f64, JsValue> {
+    Ok(stats::population_std_dev(&values.to_vec())?)
+}
+
+/// Median of values. Throws `EMPTY_INPUT` if array is empty.
+#[wasm_bindgen]
+pub fn median(values: &Float64Array) -> Result<f64, JsValue> {
+    Ok(stats::median(&values.to_vec())?)
+}
+
+/// Sample quantile of values at probability `p`, using one of the nine
+/// Hyndman & Fan definitions. Throws `EMPTY_INPUT`, or `OUT_OF_RANGE` if
+/// `p` is outside [0, 1].
+#[wasm_bindgen]
+pub fn quantile(values: &Float64Array, p: f64, method: QuantileMethod) -> Result<f64, JsValue> {
+    Ok(stats::quantile(&values.to_vec(), p, method)?)
+}
+
+/// Sample quantiles of values at each probability in `ps`.
+/// Throws as `quantile`.
+#[wasm_bindgen]
+pub fn quantiles(
+    values: &Float64Array,
+    ps: &Float64Array,
+    method: QuantileMethod,
+) -> Result<Vec<f64>, JsValue> {
+    Ok(stats::quantiles(&values.to_vec(), &ps.to_vec(), method)?)
+}
 
-    for i in 0..n {
-        mean_a += a.get_index(i as u32);
-        mean_b += b.get_index(i as u32);
+/// Interquartile range of values. Throws `EMPTY_INPUT` if array is empty.
+#[wasm_bindgen]
+pub fn iqr(values: &Float64Array, method: QuantileMethod) -> Result<f64, JsValue> {
+    Ok(stats::iqr(&values.to_vec(), method)?)
+}
+
+/// Median absolute deviation from the median (unscaled).
+/// Throws `EMPTY_INPUT` if array is empty.
+#[wasm_bindgen]
+pub fn mad(values: &Float64Array) -> Result<f64, JsValue> {
+    Ok(stats::mad(&values.to_vec())?)
+}
+
+/// Adjusted sample skewness of values.
+/// Throws `TOO_SHORT` if fewer than 3 values, `ZERO_VARIANCE` if constant.
+#[wasm_bindgen]
+pub fn skewness(values: &Float64Array) -> Result<f64, JsValue> {
+    Ok(stats::skewness(&values.to_vec())?)
+}
+
+/// Sample excess kurtosis of values.
+/// Throws `TOO_SHORT` if fewer than 4 values, `ZERO_VARIANCE` if constant.
+#[wasm_bindgen]
+pub fn kurtosis(values: &Float64Array) -> Result<f64, JsValue> {
+    Ok(stats::kurtosis(&values.to_vec())?)
+}
+
+/// Coefficient of variation of values (sample std-dev over |mean|).
+/// Throws `TOO_SHORT` if fewer than 2 values, `ZERO_BASE` if the mean is 0.
+#[wasm_bindgen]
+pub fn coefficient_of_variation(values: &Float64Array) -> Result<f64, JsValue> {
+    Ok(stats::coefficient_of_variation(&values.to_vec())?)
+}
+
+/// Change between the first and last year of a series under `measure`
+/// (absolute, percentage points, percent, symmetric percent, log ratio or
+/// CAGR). Throws `TOO_SHORT`, `LENGTH_MISMATCH`, `ZERO_BASE`, or
+/// `NON_POSITIVE` for log ratio and CAGR.
+#[wasm_bindgen]
+pub fn change(
+    years: &Float64Array,
+    values: &Float64Array,
+    measure: ChangeMeasure,
+) -> Result<f64, JsValue> {
+    let series = year_series(years, values)?;
+    Ok(change::change(&series, measure)?)
+}
+
+/// Default change measure for an indicator unit such as `"%"` or `"USD"`.
+#[wasm_bindgen]
+pub fn default_change_measure(unit: &str) -> ChangeMeasure {
+    change::default_measure(unit)
+}
+
+/// Compound annual growth rate of a series in percent per year, using the
+/// span between its first and last years. Throws `TOO_SHORT`,
+/// `LENGTH_MISMATCH`, `NON_POSITIVE` for zero or negative endpoints, or
+/// `ZERO_VARIANCE` if every year is the same.
+#[wasm_bindgen]
+pub fn cagr(years: &Float64Array, values: &Float64Array) -> Result<f64, JsValue> {
+    let series = year_series(years, values)?;
+    Ok(growth::cagr(&series)?)
+}
+
+/// Years to double at the series' CAGR. Throws as `cagr`, or
+/// `WRONG_DIRECTION` if the series is not growing.
+#[wasm_bindgen]
+pub fn doubling_time(years: &Float64Array, values: &Float64Array) -> Result<f64, JsValue> {
+    let series = year_series(years, values)?;
+    Ok(growth::doubling_time(&series)?)
+}
+
+/// Years to halve at the series' CAGR, for declining indicators such as
+/// infant mortality. Throws as `cagr`, or `WRONG_DIRECTION` if the series
+/// is not declining.
+#[wasm_bindgen]
+pub fn halving_time(years: &Float64Array, values: &Float64Array) -> Result<f64, JsValue> {
+    let series = year_series(years, values)?;
+    Ok(growth::halving_time(&series)?)
+}
+
+/// Change over the period implied by the fitted year-aware trend line,
+/// with a confidence interval at `level` (e.g. 0.95). Returns an object with
+/// `start_year`, `end_year`, `start`, `end`, `change`, `change_lo`,
+/// `change_hi`, `pct_change`, `pct_lo`, `pct_hi` and `level`.
+/// Throws `TOO_SHORT` if fewer than 3 points, `OUT_OF_RANGE` unless
+/// 0 < level < 1.
+#[wasm_bindgen]
+pub fn fitted_change(
+    years: &Float64Array,
+    values: &Float64Array,
+    level: f64,
+) -> Result<JsValue, JsValue> {
+    to_js(&change::fitted_change(&year_series(years, values)?, level)?)
+}
+
+/// Change between the means of the first and last `window` observations,
+/// with a confidence interval at `level`. Returns the same object as
+/// `fitted_change`. Throws `OUT_OF_RANGE` unless 2 <= window <= n / 2 and
+/// 0 < level < 1.
+#[wasm_bindgen]
+pub fn trimmed_change(
+    years: &Float64Array,
+    values: &Float64Array,
+    window: usize,
+    level: f64,
+) -> Result<JsValue, JsValue> {
+    to_js(&change::trimmed_change(
+        &year_series(years, values)?,
+        window,
+        level,
+    )?)
+}
+
+/// Spearman rank correlation between two equal-length arrays.
+/// Throws `EMPTY_INPUT`, `LENGTH_MISMATCH` or `ZERO_VARIANCE`.
+#[wasm_bindgen]
+pub fn spearman(a: &Float64Array, b: &Float64Array) -> Result<f64, JsValue> {
+    Ok(correlation::spearman(&a.to_vec(), &b.to_vec())?)
+}
+
+/// Kendall tau-b rank correlation between two equal-length arrays.
+/// Throws `TOO_SHORT`, `LENGTH_MISMATCH` or `ZERO_VARIANCE`.
+#[wasm_bindgen]
+pub fn kendall(a: &Float64Array, b: &Float64Array) -> Result<f64, JsValue> {
+    Ok(correlation::kendall(&a.to_vec(), &b.to_vec())?)
+}
+
+/// Correlation between two equal-length arrays using `method`.
+/// Throws as the chosen method's own export.
+#[wasm_bindgen]
+pub fn correlation(
+    a: &Float64Array,
+    b: &Float64Array,
+    method: CorrelationMethod,
+) -> Result<f64, JsValue> {
+    Ok(correlation::correlation(&a.to_vec(), &b.to_vec(), method)?)
+}
+
+/// Correlation between two year series paired by year according to
+/// `join`, using `method`. Throws `NO_OVERLAP` if no years pair up.
+#[wasm_bindgen]
+pub fn correlation_years(
+    years_a: &Float64Array,
+    values_a: &Float64Array,
+    years_b: &Float64Array,
+    values_b: &Float64Array,
+    join: Join,
+    method: CorrelationMethod,
+) -> Result<f64, JsValue> {
+    let aligned = align_complete(years_a, values_a, years_b, values_b, join)?;
+    Ok(correlation::correlation(&aligned.a, &aligned.b, method)?)
+}
+
+/// Test a correlation between two equal-length arrays. Returns an object
+/// with `method`, `r`, `statistic`, `p_value`, `ci_lo`, `ci_hi` (Fisher-z
+/// interval at `level`), `level`, `n`, `effective_n` and a `small_sample`
+/// warning flag. Throws `TOO_SHORT` if fewer than 4 pairs, `OUT_OF_RANGE`
+/// unless 0 < level < 1, otherwise as `correlation`.
+#[wasm_bindgen]
+pub fn correlation_test(
+    a: &Float64Array,
+    b: &Float64Array,
+    method: CorrelationMethod,
+    level: f64,
+) -> Result<JsValue, JsValue> {
+    to_js(&correlation::correlation_test(
+        &a.to_vec(),
+        &b.to_vec(),
+        method,
+        level,
+    )?)
+}
+
+/// `correlation_test` on two year series paired by year according to
+/// `join`. Throws `NO_OVERLAP` if no years pair up.
+#[wasm_bindgen]
+pub fn correlation_test_years(
+    years_a: &Float64Array,
+    values_a: &Float64Array,
+    years_b: &Float64Array,
+    values_b: &Float64Array,
+    join: Join,
+    method: CorrelationMethod,
+    level: f64,
+) -> Result<JsValue, JsValue> {
+    let aligned = align_complete(years_a, values_a, years_b, values_b, join)?;
+    to_js(&correlation::correlation_test(
+        &aligned.a, &aligned.b, method, level,
+    )?)
+}
+
+/// Correlate two year series paired by year according to `join`, raw,
+/// after removing each series' linear trend, and on per-year first
+/// differences. Returns an object with `n`, `raw`, `detrended`,
+/// `differenced` and a `shared_trend` warning flag.
+/// Throws `NO_OVERLAP`, `TOO_SHORT` if fewer than 4 years pair up, or
+/// `ZERO_VARIANCE`.
+#[wasm_bindgen]
+pub fn trend_correlation(
+    years_a: &Float64Array,
+    values_a: &Float64Array,
+    years_b: &Float64Array,
+    values_b: &Float64Array,
+    join: Join,
+) -> Result<JsValue, JsValue> {
+    let aligned = align_complete(years_a, values_a, years_b, values_b, join)?;
+    to_js(&correlation::trend_correlation(
+        &aligned.years,
+        &aligned.a,
+        &aligned.b,
+    )?)
+}
+
+/// Correlate every pair among `n_series` aligned series. `values` holds the
+/// series back to back, each of equal length and covering the same years.
+/// Returns an object with `method`, `n_series`, `n_obs` and the matrices
+/// `r`, `p_value`, `p_bh` (Benjamini–Hochberg) and `p_holm` (Holm).
+/// Throws `TOO_SHORT` for fewer than 2 series or 4 observations,
+/// `LENGTH_MISMATCH` if `values` does not split evenly, or `ZERO_VARIANCE`.
+#[wasm_bindgen]
+pub fn correlation_matrix(
+    values: &Float64Array,
+    n_series: usize,
+    method: CorrelationMethod,
+) -> Result<JsValue, JsValue> {
+    let values = values.to_vec();
+    if n_series < 2 {
+        return Err(ComputeError::TooShort {
+            arg: "n_series",
+            min: 2,
+            len: n_series,
+        }
+        .into());
     }
-    mean_a /= n_f;
-    mean_b /= n_f;
-
-    let mut num = 0.0;
-    let mut den_a = 0.0;
-    let mut den_b = 0.0;
-
-    for i in 0..n {
-        let da = a.get_index(i as u32) - mean_a;
-        let db = b.get_index(i as u32) - mean_b;
-        num += da * db;
-        den_a += da * da;
-        den_b += db * db;
+    error::require_len("values", &values, n_series)?;
+    if !values.len().is_multiple_of(n_series) {
+        return Err(ComputeError::LengthMismatch {
+            arg: "values",
+            expected: n_series * (values.len() / n_series),
+            len: values.len(),
+        }
+        .into());
     }
+    let series: Vec<Vec<f64>> = values
+        .chunks(values.len() / n_series)
+        .map(<[f64]>::to_vec)
+        .collect();
+    to_js(&correlation::correlation_matrix(&series, method)?)
+}
 
-    let denom = (den_a * den_b).sqrt();
-    if denom == 0.0 {
-        f64::NAN
-    } else {
-        num / denom
-    }
+/// Adjust a family of p-values for multiple comparisons, returning them in
+/// input order. NaN entries stay NaN.
+#[wasm_bindgen]
+pub fn adjust_p_values(p_values: &Float64Array, method: PAdjustMethod) -> Vec<f64> {
+    multiple::adjust(&p_values.to_vec(), method)
+}
+
+/// Partial correlation of `x` and `y` controlling for `n_controls` control
+/// series stored back to back in `controls`, all aligned on the same years.
+/// Returns an object with `r`, `t`, `df`, `p_value`, `ci_lo`, `ci_hi`,
+/// `level`, `n`, `n_controls` and `small_sample`.
+/// Throws `TOO_SHORT` if n < n_controls + 4, `LENGTH_MISMATCH`,
+/// `SINGULAR` for collinear controls, or `ZERO_VARIANCE` if the controls
+/// explain `x` or `y` completely, and `OUT_OF_RANGE` unless 0 < level < 1.
+#[wasm_bindgen]
+pub fn partial_correlation(
+    x: &Float64Array,
+    y: &Float64Array,
+    controls: &Float64Array,
+    n_controls: usize,
+    level: f64,
+) -> Result<JsValue, JsValue> {
+    let x = x.to_vec();
+    let controls = split_series("controls", &controls.to_vec(), n_controls, x.len())?;
+    to_js(&correlation::partial_correlation(
+        &x,
+        &y.to_vec(),
+        &controls,
+        level,
+    )?)
+}
+
+/// Split `count` back-to-back series of `len` values each.
+fn split_series(
+    arg: &'static str,
+    values: &[f64],
+    count: usize,
+    len: usize,
+) -> Result<Vec<Vec<f64>>, ComputeError> {
+    error::require_same_len(arg, values, count * len)?;
+    Ok(values.chunks(len.max(1)).map(<[f64]>::to_vec).collect())
+}
+
+/// Correlate `a` at year t with `b` at year t + lag for every whole-year lag
+/// from `min_lag` to `max_lag`; positive lags mean `a` leads. Returns an
+/// object with `lags` (each `lag`, `r`, `p_value`, `n`, `effective_n`),
+/// `best_lag`, `best_r`, `best_p_value` and `best_p_adjusted`. Lags
+/// beyond the span of the two series' years are skipped.
+/// Throws `OUT_OF_RANGE` if `max_lag < min_lag`, or `TOO_SHORT` if no lag
+/// has 4 year pairs.
+#[wasm_bindgen]
+pub fn cross_correlation(
+    years_a: &Float64Array,
+    values_a: &Float64Array,
+    years_b: &Float64Array,
+    values_b: &Float64Array,
+    min_lag: i32,
+    max_lag: i32,
+) -> Result<JsValue, JsValue> {
+    let a = YearSeries::from_arrays("years_a", &years_a.to_vec(), &values_a.to_vec())?;
+    let b = YearSeries::from_arrays("years_b", &years_b.to_vec(), &values_b.to_vec())?;
+    to_js(&correlation::cross_correlation(&a, &b, min_lag, max_lag)?)
+}
+
+/// Trailing `window`-year rolling mean, one point per observed year, each
+/// an object with `year`, `value` and `n`. `value` is `null` where fewer
+/// than `min_periods` observations fall in the window.
+/// Throws `OUT_OF_RANGE` unless 1 <= min_periods <= window.
+#[wasm_bindgen]
+pub fn rolling_mean(
+    years: &Float64Array,
+    values: &Float64Array,
+    window: usize,
+    min_periods: usize,
+) -> Result<JsValue, JsValue> {
+    let series = year_series(years, values)?;
+    to_js(&rolling::rolling(
+        &series,
+        window,
+        min_periods,
+        rolling::RollingStat::Mean,
+    )?)
+}
+
+/// Trailing rolling sample standard deviation; see `rolling_mean`.
+#[wasm_bindgen]
+pub fn rolling_std_dev(
+    years: &Float64Array,
+    values: &Float64Array,
+    window: usize,
+    min_periods: usize,
+) -> Result<JsValue, JsValue> {
+    let series = year_series(years, values)?;
+    to_js(&rolling::rolling(
+        &series,
+        window,
+        min_periods,
+        rolling::RollingStat::StdDev,
+    )?)
+}
+
+/// Trailing rolling year-aware trend slope; see `rolling_mean`.
+#[wasm_bindgen]
+pub fn rolling_slope(
+    years: &Float64Array,
+    values: &Float64Array,
+    window: usize,
+    min_periods: usize,
+) -> Result<JsValue, JsValue> {
+    let series = year_series(years, values)?;
+    to_js(&rolling::rolling(
+        &series,
+        window,
+        min_periods,
+        rolling::RollingStat::Slope,
+    )?)
+}
+
+/// Trailing rolling Pearson correlation of two year series paired by year
+/// according to `join`; see `rolling_mean`. Throws `NO_OVERLAP` if no
+/// years pair up.
+#[wasm_bindgen]
+pub fn rolling_pearson(
+    years_a: &Float64Array,
+    values_a: &Float64Array,
+    years_b: &Float64Array,
+    values_b: &Float64Array,
+    join: Join,
+    window: usize,
+    min_periods: usize,
+) -> Result<JsValue, JsValue> {
+    let aligned = align_complete(years_a, values_a, years_b, values_b, join)?;
+    to_js(&rolling::rolling_pearson(&aligned, window, min_periods)?)
+}
+
+/// Granger causality F tests in both directions between two evenly spaced
+/// year series paired by `join`. The lag order (1 to `max_lag`) is chosen
+/// by `criterion` on a bivariate VAR. Returns an object with `lag_order`,
+/// `criterion_values`, `n`, and `a_causes_b` and `b_causes_a` (each with
+/// `f_stat`, `df1`, `df2` and `p_value`).
+/// Throws `TOO_SHORT` with fewer than `3 * max_lag + 2` paired years,
+/// `IRREGULAR_SPACING` if paired years have gaps, or `SINGULAR` if the
+/// lagged values are collinear. Besides a constant series, that includes
+/// any series that is an exact straight line in time once `max_lag` is 2
+/// or more (or when both series are lines), since successive lags of a
+/// line differ by a constant.
+#[wasm_bindgen]
+pub fn granger(
+    years_a: &Float64Array,
+    values_a: &Float64Array,
+    years_b: &Float64Array,
+    values_b: &Float64Array,
+    join: Join,
+    max_lag: usize,
+    criterion: InformationCriterion,
+) -> Result<JsValue, JsValue> {
+    let aligned = align_complete(years_a, values_a, years_b, values_b, join)?;
+    to_js(&granger::granger(&aligned, max_lag, criterion)?)
+}
+
+/// Augmented Dickey–Fuller unit-root test on an evenly spaced year series.
+/// The lag order is chosen by `criterion` up to `max_lag` (by default
+/// Schwert's rule). Returns an object with `statistic`, `lags`, `n`,
+/// `critical_values` (`pct1`, `pct5`, `pct10`, from MacKinnon) and
+/// `stationary` (unit root rejected at 5%).
+/// Throws `TOO_SHORT` with fewer than 8 years, `IRREGULAR_SPACING` on gaps,
+/// or `OUT_OF_RANGE` if `max_lag` is too long for the series.
+#[wasm_bindgen]
+pub fn adf(
+    years: &Float64Array,
+    values: &Float64Array,
+    deterministic: Deterministic,
+    max_lag: Option<usize>,
+    criterion: InformationCriterion,
+) -> Result<JsValue, JsValue> {
+    let series = year_series(years, values)?;
+    to_js(&stationarity::adf(
+        &series,
+        deterministic,
+        max_lag,
+        criterion,
+    )?)
+}
+
+/// KPSS stationarity test on an evenly spaced year series, with `lags`
+/// Bartlett lags (by default `4 (n / 100)^(1/4)`). Returns an object with
+/// `statistic`, `lags`, `n`, `p_value` (interpolated, clamped to
+/// [0.01, 0.10]), `critical_values` and `stationary` (not rejected at 5%).
+/// Throws as `adf`.
+#[wasm_bindgen]
+pub fn kpss(
+    years: &Float64Array,
+    values: &Float64Array,
+    deterministic: Deterministic,
+    lags: Option<usize>,
+) -> Result<JsValue, JsValue> {
+    let series = year_series(years, values)?;
+    to_js(&stationarity::kpss(&series, deterministic, lags)?)
+}
+
+/// Engle–Granger cointegration test of `a` on `b`, paired by `join`.
+/// Returns an object with the cointegrating `intercept` and `slope`, the
+/// residual ADF `statistic`, `lags`, `n`, `critical_values` and
+/// `cointegrated` (rejected at 5%). Throws as `adf`, or `NO_OVERLAP`.
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn engle_granger(
+    years_a: &Float64Array,
+    values_a: &Float64Array,
+    years_b: &Float64Array,
+    values_b: &Float64Array,
+    join: Join,
+    deterministic: Deterministic,
+    max_lag: Option<usize>,
+    criterion: InformationCriterion,
+) -> Result<JsValue, JsValue> {
+    let aligned = align_complete(years_a, values_a, years_b, values_b, join)?;
+    to_js(&stationarity::engle_granger(
+        &aligned,
+        deterministic,
+        max_lag,
+        criterion,
+    )?)
+}
+
+/// Multiple linear regression of `y` on `n_regressors` columns stored back
+/// to back in `x`, each as long as `y`, with an intercept if `intercept`.
+/// Returns an object with `coefficients` (intercept first), `std_errors`,
+/// `t_stats`, `p_values`, `hc1_std_errors`, `hc3_std_errors`, `r_squared`,
+/// `adj_r_squared`, `f_stat`, `f_p_value`, `df_model`, `df_resid`,
+/// `residuals`, `vif`, `durbin_watson` and `n`.
+/// Throws `LENGTH_MISMATCH` if `x` is not `n_regressors` columns,
+/// `TOO_SHORT` without more observations than coefficients, or `SINGULAR`
+/// if the regressors are collinear.
+#[wasm_bindgen]
+pub fn ols(
+    y: &Float64Array,
+    x: &Float64Array,
+    n_regressors: usize,
+    intercept: bool,
+) -> Result<JsValue, JsValue> {
+    let y = y.to_vec();
+    let columns = split_series("x", &x.to_vec(), n_regressors, y.len())?;
+    to_js(&ols::ols(&y, &columns, intercept)?)
+}
+
+/// Fixed- or random-effects regression on a long-format panel: row i is
+/// entity `entities[i]` (e.g. a geography id) in year `years[i]`, with
+/// outcome `y[i]` and `n_regressors` columns stored back to back in `x`.
+/// `time_effects` adds a dummy per year. Returns an object with
+/// `coefficients` (intercept first for random effects), `std_errors`,
+/// `p_values`, `clustered_std_errors` and `clustered_p_values` (clustered
+/// by entity), `r_squared_within`, `sigma_e`, `sigma_u`, `n`,
+/// `n_entities`, `n_periods` and `df_resid`.
+/// Throws `LENGTH_MISMATCH` if rows disagree in length, `TOO_SHORT` with
+/// fewer than 2 entities or too few rows for the coefficients, or
+/// `SINGULAR` if a regressor has no variation within entities.
+#[wasm_bindgen]
+pub fn panel_regression(
+    entities: &Uint32Array,
+    years: &Float64Array,
+    y: &Float64Array,
+    x: &Float64Array,
+    n_regressors: usize,
+    model: PanelModel,
+    time_effects: bool,
+) -> Result<JsValue, JsValue> {
+    let y = y.to_vec();
+    let columns = split_series("x", &x.to_vec(), n_regressors, y.len())?;
+    to_js(&panel::panel_regression(
+        &entities.to_vec(),
+        &years.to_vec(),
+        &y,
+        &columns,
+        model,
+        time_effects,
+    )?)
+}
+
+/// Elasticity of `y` with respect to `x` from a log-log regression over
+/// paired observations, e.g. many geographies in one year. Returns an
+/// object with `elasticity` (percent change in `y` per 1% change in `x`),
+/// `std_error`, `ci_lo`, `ci_hi`, `level`, `p_value`, `r_squared` and `n`.
+/// Throws `NON_POSITIVE` if any value is zero or negative, `TOO_SHORT`
+/// with fewer than 3 pairs, `ZERO_VARIANCE` if `x` is constant, or
+/// `OUT_OF_RANGE` unless 0 < level < 1.
+#[wasm_bindgen]
+pub fn elasticity(x: &Float64Array, y: &Float64Array, level: f64) -> Result<JsValue, JsValue> {
+    to_js(&elasticity::elasticity(&x.to_vec(), &y.to_vec(), level)?)
+}
+
+/// `elasticity` of `b` with respect to `a` over time, pairing the two year
+/// series by `join`. Throws as `elasticity`, or `NO_OVERLAP`.
+#[wasm_bindgen]
+pub fn elasticity_years(
+    years_a: &Float64Array,
+    values_a: &Float64Array,
+    years_b: &Float64Array,
+    values_b: &Float64Array,
+    join: Join,
+    level: f64,
+) -> Result<JsValue, JsValue> {
+    let aligned = align_complete(years_a, values_a, years_b, values_b, join)?;
+    to_js(&elasticity::elasticity(&aligned.a, &aligned.b, level)?)
+}
+
+/// Forecast the year-aware linear trend `horizon` steps past the last
+/// observation, stepping by the smallest gap between years. Returns an
+/// array of `{year, point, lo80, hi80, lo95, hi95}`, with 80% and 95%
+/// prediction intervals.
+/// Throws `TOO_SHORT` with fewer than 3 points, `OUT_OF_RANGE` if
+/// `horizon` is 0, or `ZERO_VARIANCE` if all years are equal.
+#[wasm_bindgen]
+pub fn forecast(
+    years: &Float64Array,
+    values: &Float64Array,
+    horizon: usize,
+) -> Result<JsValue, JsValue> {
+    let series = year_series(years, values)?;
+    to_js(&forecast::linear_forecast(&series, horizon)?)
+}
+
+/// Fit an exponential smoothing model to an evenly spaced year series and
+/// forecast `horizon` steps ahead. Returns an object with `alpha`, `beta`,
+/// `phi`, `initial_level`, `initial_trend` (`null` where the model has no
+/// such term), `sse`, `sigma`, `log_lik`, `aic`, `aicc`, `bic`,
+/// `residuals`, `n` and `forecast` (an array of `{year, point, lo80, hi80,
+/// lo95, hi95}`).
+/// Throws `TOO_SHORT` if the series is too short for the model,
+/// `IRREGULAR_SPACING` on gaps, or `OUT_OF_RANGE` if `horizon` is 0.
+#[wasm_bindgen]
+pub fn ets(
+    years: &Float64Array,
+    values: &Float64Array,
+    model: EtsModel,
+    horizon: usize,
+) -> Result<JsValue, JsValue> {
+    let series = year_series(years, values)?;
+    to_js(&ets::ets(&series, model, horizon)?)
+}
+
+/// Fit ARIMA(`p`, `d`, `q`) to an evenly spaced year series by conditional
+/// sum of squares and forecast `horizon` steps ahead. Returns an object
+/// with `p`, `d`, `q`, `ar`, `ma`, `mean` (`null` when d = 2), `sse`,
+/// `sigma`, `log_lik`, `aic`, `aicc`, `bic`, `residuals`, `n` and
+/// `forecast` (an array of `{year, point, lo80, hi80, lo95, hi95}`).
+/// Throws `TOO_SHORT` if the series is too short for the order,
+/// `IRREGULAR_SPACING` on gaps, or `OUT_OF_RANGE` if `d` exceeds 2 or
+/// `horizon` is 0.
+#[wasm_bindgen]
+pub fn arima(
+    years: &Float64Array,
+    values: &Float64Array,
+    p: usize,
+    d: usize,
+    q: usize,
+    horizon: usize,
+) -> Result<JsValue, JsValue> {
+    let series = year_series(years, values)?;
+    to_js(&arima::arima(&series, p, d, q, horizon)?)
+}
+
+/// `arima` with the order chosen automatically: `d` by repeated KPSS
+/// tests, then `p` up to `max_p` and `q` up to `max_q` by AICc. Throws as
+/// `arima`, with `TOO_SHORT` below 10 points.
+#[wasm_bindgen]
+pub fn auto_arima(
+    years: &Float64Array,
+    values: &Float64Array,
+    max_p: usize,
+    max_q: usize,
+    horizon: usize,
+) -> Result<JsValue, JsValue> {
+    let series = year_series(years, values)?;
+    to_js(&arima::auto_arima(&series, max_p, max_q, horizon)?)
+}
+
+/// Fit a logistic or Gompertz `curve` running between the fixed bounds
+/// `lo` and `hi` (e.g. 0 and 100 for a percentage) and forecast `horizon`
+/// steps past the last observation. Returns an object with `rate`,
+/// `midpoint`, `lo`, `hi`, `sse`, `sigma`, `r_squared`, `n` and
+/// `forecast` (an array of `{year, point, lo80, hi80, lo95, hi95}`, all
+/// within the bounds).
+/// Throws `OUT_OF_RANGE` if `lo >= hi`, a value lies outside the bounds
+/// or `horizon` is 0, or `TOO_SHORT` with fewer than 3 points.
+#[wasm_bindgen]
+pub fn growth_curve(
+    years: &Float64Array,
+    values: &Float64Array,
+    curve: GrowthCurve,
+    lo: f64,
+    hi: f64,
+    horizon: usize,
+) -> Result<JsValue, JsValue> {
+    let series = year_series(years, values)?;
+    to_js(&bounded::growth_curve(&series, curve, lo, hi, horizon)?)
+}
+
+/// Forecast like `forecast`, but on the logit scale between `lo` and `hi`,
+/// so points and intervals never cross the bounds. Throws as
+/// `growth_curve`; values must lie strictly inside the bounds.
+#[wasm_bindgen]
+pub fn logit_forecast(
+    years: &Float64Array,
+    values: &Float64Array,
+    lo: f64,
+    hi: f64,
+    horizon: usize,
+) -> Result<JsValue, JsValue> {
+    let series = year_series(years, values)?;
+    to_js(&bounded::logit_forecast(&series, lo, hi, horizon)?)
+}
+
+/// Rolling-origin backtest of a forecast `method` on an evenly spaced year
+/// series: train on the first `min_train` points, then one more at a time,
+/// forecasting up to `max_horizon` steps from each origin. Returns an
+/// object with `origins` and `horizons`, one entry per horizon with `n`,
+/// `mae`, `rmse`, `mape`, `smape` and `mase` (`null` where undefined).
+/// Throws `OUT_OF_RANGE` unless 2 <= min_train < n and max_horizon >= 1,
+/// `IRREGULAR_SPACING` on gaps, or whatever the method throws on the
+/// shortest training window. Forecasts are scored against the value
+/// observed in their year.
+#[wasm_bindgen]
+pub fn backtest(
+    years: &Float64Array,
+    values: &Float64Array,
+    method: ForecastMethod,
+    min_train: usize,
+    max_horizon: usize,
+) -> Result<JsValue, JsValue> {
+    let series = year_series(years, values)?;
+    to_js(&backtest::backtest(
+        &series,
+        |train, horizon| method.forecast(train, horizon),
+        min_train,
+        max_horizon,
+    )?)
 }
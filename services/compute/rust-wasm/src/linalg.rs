@@ -0,0 +1,247 @@
+//! Small dense linear algebra for the regression code. Matrices here are a
+//! few columns wide, so clarity wins over blocking or SIMD.
+
+/// Row-major dense matrix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<f64>,
+}
+
+impl Matrix {
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Matrix {
+            rows,
+            cols,
+            data: vec![0.0; rows * cols],
+        }
+    }
+
+    /// Build a matrix whose columns are the given slices, all of length
+    /// `rows`. With `intercept`, a leading column of ones is added.
+    pub fn from_columns(rows: usize, columns: &[&[f64]], intercept: bool) -> Self {
+        let offset = usize::from(intercept);
+        let mut m = Matrix::zeros(rows, columns.len() + offset);
+        for i in 0..rows {
+            if intercept {
+                m[(i, 0)] = 1.0;
+            }
+            for (j, col) in columns.iter().enumerate() {
+                m[(i, j + offset)] = col[i];
+            }
+        }
+        m
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn row(&self, i: usize) -> &[f64] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    /// `self * v`.
+    pub fn mul_vec(&self, v: &[f64]) -> Vec<f64> {
+        (0..self.rows)
+            .map(|i| self.row(i).iter().zip(v).map(|(a, b)| a * b).sum())
+            .collect()
+    }
+
+    /// `selfᵀ * self`.
+    pub fn gram(&self) -> Matrix {
+        let mut g = Matrix::zeros(self.cols, self.cols);
+        for i in 0..self.rows {
+            let row = self.row(i);
+            for j in 0..self.cols {
+                for k in j..self.cols {
+                    g[(j, k)] += row[j] * row[k];
+                }
+            }
+        }
+        for j in 0..self.cols {
+            for k in 0..j {
+                g[(j, k)] = g[(k, j)];
+            }
+        }
+        g
+    }
+
+    /// Inverse of a square matrix by Gauss–Jordan elimination with partial
+    /// pivoting. Returns `None` if the matrix is numerically singular.
+    pub fn invert(&self) -> Option<Matrix> {
+        let n = self.rows;
+        if self.cols != n {
+            return None;
+        }
+        let mut a = self.clone();
+        let mut inv = Matrix::zeros(n, n);
+        for i in 0..n {
+            inv[(i, i)] = 1.0;
+        }
+        let scale = self.data.iter().fold(0.0, |m: f64, v| m.max(v.abs()));
+        for k in 0..n {
+            let pivot = (k..n).max_by(|&i, &j| a[(i, k)].abs().total_cmp(&a[(j, k)].abs()))?;
+            if a[(pivot, k)].abs() <= 1e-12 * scale.max(1.0) {
+                return None;
+            }
+            for j in 0..n {
+                a.data.swap(k * n + j, pivot * n + j);
+                inv.data.swap(k * n + j, pivot * n + j);
+            }
+            let d = a[(k, k)];
+            for j in 0..n {
+                a[(k, j)] /= d;
+                inv[(k, j)] /= d;
+            }
+            for i in (0..n).filter(|&i| i != k) {
+                let f = a[(i, k)];
+                if f != 0.0 {
+                    for j in 0..n {
+                        a[(i, j)] -= f * a[(k, j)];
+                        inv[(i, j)] -= f * inv[(k, j)];
+                    }
+                }
+            }
+        }
+        Some(inv)
+    }
+}
+
+impl std::ops::Index<(usize, usize)> for Matrix {
+    type Output = f64;
+
+    fn index(&self, (i, j): (usize, usize)) -> &f64 {
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl std::ops::IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut f64 {
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+/// Least-squares solution of `x * coef ≈ y`.
+#[derive(Debug, Clone)]
+pub struct LeastSquares {
+    pub coef: Vec<f64>,
+    /// `y - x * coef`.
+    pub residuals: Vec<f64>,
+    /// Residual sum of squares.
+    pub sse: f64,
+}
+
+/// Solve `min |y - x b|` by Householder QR. Returns `None` if `x` has
+/// fewer rows than columns or is numerically rank deficient.
+pub fn lstsq(x: &Matrix, y: &[f64]) -> Option<LeastSquares> {
+    let (n, p) = (x.rows, x.cols);
+    if n < p || y.len() != n {
+        return None;
+    }
+    let mut a = x.clone();
+    let mut qty = y.to_vec();
+    let scale = (0..p)
+        .map(|j| (0..n).map(|i| a[(i, j)].abs()).fold(0.0, f64::max))
+        .fold(0.0, f64::max);
+
+    for k in 0..p {
+        let norm = (k..n).map(|i| a[(i, k)] * a[(i, k)]).sum::<f64>().sqrt();
+        if norm <= 1e-12 * scale.max(1.0) * (n as f64).sqrt() {
+            return None;
+        }
+        let alpha = if a[(k, k)] > 0.0 { -norm } else { norm };
+        // Householder vector v = a[k.., k] - alpha e1, stored in place.
+        a[(k, k)] -= alpha;
+        let v_norm_sq: f64 = (k..n).map(|i| a[(i, k)] * a[(i, k)]).sum();
+        for j in k + 1..p {
+            let dot: f64 = (k..n).map(|i| a[(i, k)] * a[(i, j)]).sum();
+            let f = 2.0 * dot / v_norm_sq;
+            for i in k..n {
+                a[(i, j)] -= f * a[(i, k)];
+            }
+        }
+        let dot: f64 = (k..n).map(|i| a[(i, k)] * qty[i]).sum();
+        let f = 2.0 * dot / v_norm_sq;
+        for i in k..n {
+            qty[i] -= f * a[(i, k)];
+        }
+        a[(k, k)] = alpha;
+    }
+
+    // Back-substitute R b = Q'y; R's strict upper triangle is in `a`.
+    let mut coef = vec![0.0; p];
+    for k in (0..p).rev() {
+        let s: f64 = (k + 1..p).map(|j| a[(k, j)] * coef[j]).sum();
+        coef[k] = (qty[k] - s) / a[(k, k)];
+    }
+
+    let fitted = x.mul_vec(&coef);
+    let residuals: Vec<f64> = y.iter().zip(&fitted).map(|(y, f)| y - f).collect();
+    let sse = residuals.iter().map(|r| r * r).sum();
+    Some(LeastSquares {
+        coef,
+        residuals,
+        sse,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    const Z: [f64; 10] = [1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0, 5.0, 6.0, 6.0];
+    const W: [f64; 10] = [5.0, 3.0, 4.0, 2.0, 4.0, 1.0, 3.0, 2.0, 1.0, 0.0];
+
+    #[test]
+    fn lstsq_recovers_an_exact_fit() {
+        let x = Matrix::from_columns(10, &[&Z, &W], true);
+        let y: Vec<f64> = Z
+            .iter()
+            .zip(W)
+            .map(|(z, w)| 1.0 + 2.0 * z - 3.0 * w)
+            .collect();
+        let fit = lstsq(&x, &y).unwrap();
+        for (c, e) in fit.coef.iter().zip([1.0, 2.0, -3.0]) {
+            assert_close(*c, e, 1e-12);
+        }
+        assert!(fit.sse < 1e-20);
+    }
+
+    #[test]
+    fn lstsq_matches_normal_equations() {
+        let x = Matrix::from_columns(10, &[&Z, &W], true);
+        let y = [2.0, 4.0, 5.0, 5.0, 8.0, 7.0, 9.0, 8.0, 11.0, 13.0];
+        let fit = lstsq(&x, &y).unwrap();
+        // (X'X)^-1 X'y, solved exactly.
+        let expected = [
+            -2.115_686_274_509_804,
+            2.225_490_196_078_431_4,
+            0.521_568_627_450_980_4,
+        ];
+        for (c, e) in fit.coef.iter().zip(expected) {
+            assert_close(*c, e, 1e-12);
+        }
+        assert_close(fit.sse, 11.476_470_588_235_294, 1e-12);
+        // Residuals are orthogonal to every column.
+        for j in 0..x.cols() {
+            let dot: f64 = (0..10).map(|i| x[(i, j)] * fit.residuals[i]).sum();
+            assert!(dot.abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn lstsq_rejects_rank_deficient_designs() {
+        let doubled: Vec<f64> = Z.iter().map(|z| 2.0 * z).collect();
+        let x = Matrix::from_columns(10, &[&Z, &doubled], true);
+        assert!(lstsq(&x, &W).is_none());
+        let wide = Matrix::from_columns(2, &[&Z[..2], &W[..2]], true);
+        assert!(lstsq(&wide, &[1.0, 2.0]).is_none());
+    }
+}
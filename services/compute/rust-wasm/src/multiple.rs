@@ -0,0 +1,108 @@
+//! Multiple-testing corrections for families of p-values.
+
+use wasm_bindgen::prelude::*;
+
+/// Adjustment applied to a family of p-values.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PAdjustMethod {
+    /// Benjamini–Hochberg step-up; controls the false discovery rate.
+    BenjaminiHochberg = 0,
+    /// Holm step-down; controls the family-wise error rate.
+    Holm = 1,
+}
+
+/// Adjust `p` for multiple comparisons, returning values in input order
+/// (as R's `p.adjust`). NaN p-values are left as NaN and do not count
+/// towards the family size.
+pub fn adjust(p: &[f64], method: PAdjustMethod) -> Vec<f64> {
+    match method {
+        PAdjustMethod::BenjaminiHochberg => benjamini_hochberg(p),
+        PAdjustMethod::Holm => holm(p),
+    }
+}
+
+/// Benjamini–Hochberg adjusted p-values.
+pub fn benjamini_hochberg(p: &[f64]) -> Vec<f64> {
+    let order = sorted_indices(p);
+    let m = order.len() as f64;
+    let mut out = vec![f64::NAN; p.len()];
+    // Walk from the largest p down, carrying the running minimum.
+    let mut running = 1.0_f64;
+    for (rank, &i) in order.iter().enumerate().rev() {
+        running = running.min(p[i] * m / (rank + 1) as f64);
+        out[i] = running;
+    }
+    out
+}
+
+/// Holm adjusted p-values.
+pub fn holm(p: &[f64]) -> Vec<f64> {
+    let order = sorted_indices(p);
+    let m = order.len() as f64;
+    let mut out = vec![f64::NAN; p.len()];
+    // Walk from the smallest p up, carrying the running maximum.
+    let mut running = 0.0_f64;
+    for (rank, &i) in order.iter().enumerate() {
+        running = running.max((p[i] * (m - rank as f64)).min(1.0));
+        out[i] = running;
+    }
+    out
+}
+
+/// Indices of the non-NaN entries of `p`, in ascending order of p.
+fn sorted_indices(p: &[f64]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..p.len()).filter(|&i| !p[i].is_nan()).collect();
+    order.sort_by(|&i, &j| p[i].total_cmp(&p[j]));
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    const NAN: f64 = f64::NAN;
+    // Ties at 0.04 and a NaN that must not count towards the family.
+    const P: [f64; 9] = [0.01, 0.04, 0.03, 0.04, 0.2, 0.002, 0.9, 0.35, NAN];
+
+    #[track_caller]
+    fn assert_adjusted(actual: Vec<f64>, expected: [f64; 9]) {
+        for (a, e) in actual.into_iter().zip(expected) {
+            if e.is_nan() {
+                assert!(a.is_nan(), "{a} != NaN");
+            } else {
+                assert_close(a, e, 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn benjamini_hochberg_matches_r() {
+        // p.adjust(p, "BH") in R; the running minimum pulls 0.03 and both
+        // 0.04s down to 0.064.
+        assert_adjusted(
+            adjust(&P, PAdjustMethod::BenjaminiHochberg),
+            [0.04, 0.064, 0.064, 0.064, 0.8 / 3.0, 0.016, 0.9, 0.4, NAN],
+        );
+    }
+
+    #[test]
+    fn holm_matches_r() {
+        // p.adjust(p, "holm") in R; the running maximum lifts the second
+        // 0.04 from 0.16 to 0.2.
+        assert_adjusted(
+            adjust(&P, PAdjustMethod::Holm),
+            [0.07, 0.2, 0.18, 0.2, 0.6, 0.016, 0.9, 0.7, NAN],
+        );
+    }
+
+    #[test]
+    fn adjusted_p_values_are_capped_at_one() {
+        let p = [0.4, 0.45, 0.6];
+        assert_eq!(holm(&p), [1.0, 1.0, 1.0]);
+        assert_eq!(benjamini_hochberg(&p), [0.6, 0.6, 0.6]);
+        assert_eq!(benjamini_hochberg(&[1.0, 1.0]), [1.0, 1.0]);
+        assert!(holm(&[]).is_empty());
+    }
+}
@@ -0,0 +1,275 @@
+use serde::Serialize;
+
+use crate::dist;
+use crate::error::{require_len, require_same_len, ComputeError};
+use crate::linalg::{self, Matrix};
+
+/// Result of a multiple linear regression. Coefficient vectors start with
+/// the intercept when one was fitted, then follow the regressor order.
+#[derive(Debug, Clone, Serialize)]
+pub struct OlsFit {
+    pub coefficients: Vec<f64>,
+    /// Classical standard errors, assuming homoskedastic errors.
+    pub std_errors: Vec<f64>,
+    pub t_stats: Vec<f64>,
+    /// Two-sided p-values from the t distribution with `df_resid` degrees
+    /// of freedom.
+    pub p_values: Vec<f64>,
+    /// White heteroskedasticity-robust standard errors, scaled by
+    /// `n / df_resid`.
+    pub hc1_std_errors: Vec<f64>,
+    /// Robust standard errors weighting each residual by its leverage;
+    /// better behaved than HC1 in small samples. Infinite when a point has
+    /// leverage 1.
+    pub hc3_std_errors: Vec<f64>,
+    /// Centered with an intercept, uncentered without. NaN when every y is
+    /// equal.
+    pub r_squared: f64,
+    pub adj_r_squared: f64,
+    /// F statistic for all slopes being zero. NaN with no regressors.
+    pub f_stat: f64,
+    pub f_p_value: f64,
+    pub df_model: usize,
+    pub df_resid: usize,
+    /// Observed minus fitted value, in input order.
+    pub residuals: Vec<f64>,
+    /// Variance inflation factor of each regressor (not the intercept):
+    /// how much collinearity with the others inflates its variance.
+    pub vif: Vec<f64>,
+    /// Durbin–Watson statistic of the residuals in input order; near 2
+    /// when successive residuals are uncorrelated.
+    pub durbin_watson: f64,
+    pub n: usize,
+}
+
+/// Ordinary least squares of `y` on the regressor `columns`, each the same
+/// length as `y`, with an intercept if `intercept`.
+///
+/// Needs more observations than coefficients. Durbin–Watson treats the
+/// input order as time order, so it is only meaningful for series.
+pub fn ols(y: &[f64], columns: &[Vec<f64>], intercept: bool) -> Result<OlsFit, ComputeError> {
+    let k = columns.len() + usize::from(intercept);
+    require_len("y", y, k + 1)?;
+    for column in columns {
+        require_same_len("x", column, y.len())?;
+    }
+    let n = y.len();
+    let columns: Vec<&[f64]> = columns.iter().map(Vec::as_slice).collect();
+    let x = Matrix::from_columns(n, &columns, intercept);
+    let fit = linalg::lstsq(&x, y).ok_or(ComputeError::Singular { arg: "x" })?;
+    let xtx_inv = x
+        .gram()
+        .invert()
+        .ok_or(ComputeError::Singular { arg: "x" })?;
+
+    let df_resid = n - k;
+    let df_model = columns.len();
+    let sigma2 = fit.sse / df_resid as f64;
+    let std_errors: Vec<f64> = (0..k).map(|j| (sigma2 * xtx_inv[(j, j)]).sqrt()).collect();
+    let t_stats: Vec<f64> = fit
+        .coef
+        .iter()
+        .zip(&std_errors)
+        .map(|(b, se)| b / se)
+        .collect();
+    let p_values = t_stats
+        .iter()
+        .map(|&t| dist::student_t_two_sided_p(t, df_resid as f64))
+        .collect();
+
+    // Robust covariances are (X'X)^-1 X' diag(w) X (X'X)^-1; only the
+    // diagonal is needed, which is sum_i w_i ((X'X)^-1 x_i)_j^2.
+    let mut hc0 = vec![0.0; k];
+    let mut hc3 = vec![0.0; k];
+    for (i, e) in fit.residuals.iter().enumerate() {
+        let a = xtx_inv.mul_vec(x.row(i));
+        let leverage: f64 = x.row(i).iter().zip(&a).map(|(x, a)| x * a).sum();
+        let w3 = e * e / (1.0 - leverage).powi(2);
+        for j in 0..k {
+            hc0[j] += e * e * a[j] * a[j];
+            hc3[j] += w3 * a[j] * a[j];
+        }
+    }
+    let hc1_scale = n as f64 / df_resid as f64;
+    let hc1_std_errors = hc0.iter().map(|v| (v * hc1_scale).sqrt()).collect();
+    let hc3_std_errors = hc3.iter().map(|v| v.sqrt()).collect();
+
+    let r_squared = r_squared_of(y, fit.sse, intercept);
+    let adj_r_squared =
+        1.0 - (1.0 - r_squared) * (n - usize::from(intercept)) as f64 / df_resid as f64;
+    let f_stat = if df_model == 0 {
+        f64::NAN
+    } else {
+        (r_squared / df_model as f64) / ((1.0 - r_squared) / df_resid as f64)
+    };
+
+    let vif = (0..columns.len())
+        .map(|j| {
+            if columns.len() == 1 {
+                return Ok(1.0);
+            }
+            let others: Vec<&[f64]> = (0..columns.len())
+                .filter(|&i| i != j)
+                .map(|i| columns[i])
+                .collect();
+            let aux = linalg::lstsq(&Matrix::from_columns(n, &others, intercept), columns[j])
+                .ok_or(ComputeError::Singular { arg: "x" })?;
+            Ok(1.0 / (1.0 - r_squared_of(columns[j], aux.sse, intercept)))
+        })
+        .collect::<Result<Vec<f64>, ComputeError>>()?;
+
+    let durbin_watson = fit
+        .residuals
+        .windows(2)
+        .map(|w| (w[1] - w[0]).powi(2))
+        .sum::<f64>()
+        / fit.sse;
+
+    Ok(OlsFit {
+        coefficients: fit.coef,
+        std_errors,
+        t_stats,
+        p_values,
+        hc1_std_errors,
+        hc3_std_errors,
+        r_squared,
+        adj_r_squared,
+        f_stat,
+        f_p_value: dist::f_sf(f_stat, df_model as f64, df_resid as f64),
+        df_model,
+        df_resid,
+        residuals: fit.residuals,
+        vif,
+        durbin_watson,
+        n,
+    })
+}
+
+/// `1 - sse / tss`, with the total sum of squares taken about the mean
+/// when the model has an intercept and about zero otherwise.
+fn r_squared_of(y: &[f64], sse: f64, intercept: bool) -> f64 {
+    let center = if intercept {
+        y.iter().sum::<f64>() / y.len() as f64
+    } else {
+        0.0
+    };
+    let tss: f64 = y.iter().map(|v| (v - center).powi(2)).sum();
+    if tss == 0.0 {
+        f64::NAN
+    } else {
+        1.0 - sse / tss
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    /// R's `cars` dataset: stopping distance against speed.
+    const SPEED: [f64; 50] = [
+        4.0, 4.0, 7.0, 7.0, 8.0, 9.0, 10.0, 10.0, 10.0, 11.0, 11.0, 12.0, 12.0, 12.0, 12.0, 13.0,
+        13.0, 13.0, 13.0, 14.0, 14.0, 14.0, 14.0, 15.0, 15.0, 15.0, 16.0, 16.0, 17.0, 17.0, 17.0,
+        18.0, 18.0, 18.0, 18.0, 19.0, 19.0, 19.0, 20.0, 20.0, 20.0, 20.0, 20.0, 22.0, 23.0, 24.0,
+        24.0, 24.0, 24.0, 25.0,
+    ];
+    const DIST: [f64; 50] = [
+        2.0, 10.0, 4.0, 22.0, 16.0, 10.0, 18.0, 26.0, 34.0, 17.0, 28.0, 14.0, 20.0, 24.0, 28.0,
+        26.0, 34.0, 34.0, 46.0, 26.0, 36.0, 60.0, 80.0, 20.0, 26.0, 54.0, 32.0, 40.0, 32.0, 40.0,
+        50.0, 42.0, 56.0, 76.0, 84.0, 36.0, 46.0, 68.0, 32.0, 48.0, 52.0, 56.0, 64.0, 66.0, 54.0,
+        70.0, 92.0, 93.0, 120.0, 85.0,
+    ];
+
+    fn assert_all_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert_close(*a, *e, 1e-10);
+        }
+    }
+
+    #[test]
+    fn simple_regression_matches_r() {
+        // summary(lm(dist ~ speed, cars)), lmtest::dwtest and
+        // sandwich::vcovHC(type = "HC1" / "HC3").
+        let fit = ols(&DIST, &[SPEED.to_vec()], true).unwrap();
+        assert_all_close(
+            &fit.coefficients,
+            &[-17.579_094_890_510_95, 3.932_408_759_124_088],
+        );
+        assert_all_close(
+            &fit.std_errors,
+            &[6.758_440_169_379_236, 0.415_512_776_657_122_3],
+        );
+        assert_all_close(
+            &fit.hc1_std_errors,
+            &[5.656_149_605_872_744, 0.406_901_964_767_53],
+        );
+        assert_all_close(
+            &fit.hc3_std_errors,
+            &[5.931_803_319_074_597, 0.427_537_219_172_097_9],
+        );
+        assert_close(fit.r_squared, 0.651_079_380_758_250_9, 1e-10);
+        assert_close(
+            fit.adj_r_squared,
+            1.0 - (1.0 - fit.r_squared) * 49.0 / 48.0,
+            1e-12,
+        );
+        assert_close(fit.f_stat, 89.567_106_536_467_74, 1e-10);
+        assert_close(fit.f_p_value, 1.489_836_496_295_085e-12, 1e-8);
+        assert_close(fit.durbin_watson, 1.676_225_323_435_098, 1e-10);
+        assert_eq!((fit.df_model, fit.df_resid, fit.n), (1, 48, 50));
+        assert_eq!(fit.vif, [1.0]);
+    }
+
+    #[test]
+    fn quadratic_regression_matches_r() {
+        // lm(dist ~ speed + I(speed^2), cars).
+        let squared: Vec<f64> = SPEED.iter().map(|s| s * s).collect();
+        let fit = ols(&DIST, &[SPEED.to_vec(), squared], true).unwrap();
+        assert_all_close(
+            &fit.coefficients,
+            &[
+                2.470_137_785_066_27,
+                0.913_287_614_242_586_1,
+                0.099_959_302_069_843_91,
+            ],
+        );
+        assert_all_close(
+            &fit.std_errors,
+            &[
+                14.817_164_725_023_68,
+                2.034_220_442_311_95,
+                0.065_968_210_682_339_3,
+            ],
+        );
+        assert_all_close(
+            &fit.hc1_std_errors,
+            &[
+                9.675_100_328_713_436,
+                1.668_043_053_722_696,
+                0.060_983_008_709_222_02,
+            ],
+        );
+        assert_all_close(
+            &fit.hc3_std_errors,
+            &[
+                10.553_660_130_608_11,
+                1.789_447_241_545_791,
+                0.065_626_021_942_364_82,
+            ],
+        );
+        assert_close(fit.r_squared, 0.667_330_816_526_209_6, 1e-10);
+        assert_close(fit.durbin_watson, 1.762_358_660_180_449, 1e-10);
+        // With two regressors both VIFs are 1 / (1 - r²) of their correlation.
+        assert_all_close(&fit.vif, &[24.614_892_674_750_38, 24.614_892_674_750_38]);
+    }
+
+    #[test]
+    fn collinear_regressors_are_singular() {
+        let doubled: Vec<f64> = SPEED.iter().map(|s| 2.0 * s).collect();
+        assert_eq!(
+            ols(&DIST, &[SPEED.to_vec(), doubled], true).unwrap_err(),
+            ComputeError::Singular { arg: "x" }
+        );
+    }
+}
@@ -0,0 +1,88 @@
+//! Derivative-free minimization for the few-parameter model fits.
+
+/// Minimize `f` from `start` with the Nelder–Mead simplex method, using an
+/// initial simplex of `step` along each axis. Stops after `max_iter`
+/// iterations or once the simplex values agree to a relative 1e-10.
+///
+/// `f` may return infinity for infeasible points; they are treated as
+/// worse than any finite value.
+pub fn nelder_mead(
+    f: impl Fn(&[f64]) -> f64,
+    start: &[f64],
+    step: f64,
+    max_iter: usize,
+) -> Vec<f64> {
+    let dim = start.len();
+    let mut simplex: Vec<(Vec<f64>, f64)> = (0..=dim)
+        .map(|i| {
+            let mut x = start.to_vec();
+            if i > 0 {
+                x[i - 1] += step;
+            }
+            let fx = f(&x);
+            (x, fx)
+        })
+        .collect();
+
+    for _ in 0..max_iter {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let (best, worst) = (simplex[0].1, simplex[dim].1);
+        if (worst - best).abs() <= 1e-10 * (best.abs() + 1e-10) {
+            break;
+        }
+
+        let centroid: Vec<f64> = (0..dim)
+            .map(|j| simplex[..dim].iter().map(|(x, _)| x[j]).sum::<f64>() / dim as f64)
+            .collect();
+        let toward = |t: f64| -> Vec<f64> {
+            centroid
+                .iter()
+                .zip(&simplex[dim].0)
+                .map(|(c, w)| c + t * (w - c))
+                .collect()
+        };
+
+        let reflected = toward(-1.0);
+        let f_reflected = f(&reflected);
+        if f_reflected < best {
+            let expanded = toward(-2.0);
+            let f_expanded = f(&expanded);
+            simplex[dim] = if f_expanded < f_reflected {
+                (expanded, f_expanded)
+            } else {
+                (reflected, f_reflected)
+            };
+        } else if f_reflected < simplex[dim - 1].1 {
+            simplex[dim] = (reflected, f_reflected);
+        } else {
+            let t = if f_reflected < worst { -0.5 } else { 0.5 };
+            let contracted = toward(t);
+            let f_contracted = f(&contracted);
+            if f_contracted < worst.min(f_reflected) {
+                simplex[dim] = (contracted, f_contracted);
+            } else {
+                // Shrink everything toward the best vertex.
+                let best_x = simplex[0].0.clone();
+                for (x, fx) in simplex.iter_mut().skip(1) {
+                    for (xi, bi) in x.iter_mut().zip(&best_x) {
+                        *xi = bi + 0.5 * (*xi - bi);
+                    }
+                    *fx = f(x);
+                }
+            }
+        }
+    }
+    simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+    simplex.swap_remove(0).0
+}
+
+/// Map the real line onto (0, 1); used to keep parameters in range while
+/// optimizing without constraints.
+pub fn logistic(u: f64) -> f64 {
+    1.0 / (1.0 + (-u).exp())
+}
+
+/// Inverse of `logistic`.
+pub fn logit(p: f64) -> f64 {
+    (p / (1.0 - p)).ln()
+}
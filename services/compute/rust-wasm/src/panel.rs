@@ -0,0 +1,418 @@
+//! Linear regression on long-format panels: one row per geography and
+//! year.
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::dist;
+use crate::error::{require_same_len, ComputeError};
+use crate::linalg::{self, Matrix};
+
+/// How unobserved differences between entities are handled.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PanelModel {
+    /// Within estimator: every entity gets its own intercept, so only
+    /// variation inside an entity over time identifies the slopes.
+    #[default]
+    FixedEffects = 0,
+    /// GLS with a random entity intercept (Swamy–Arora variance
+    /// components). More efficient than fixed effects, but assumes the
+    /// entity effects are uncorrelated with the regressors.
+    RandomEffects = 1,
+}
+
+/// Result of a panel regression. Coefficient vectors start with the
+/// intercept for random effects, then follow the regressor order; time
+/// effects are estimated but not reported.
+#[derive(Debug, Clone, Serialize)]
+pub struct PanelFit {
+    pub coefficients: Vec<f64>,
+    /// Classical standard errors, assuming homoskedastic, independent
+    /// errors.
+    pub std_errors: Vec<f64>,
+    /// Two-sided p-values from the classical standard errors with
+    /// `df_resid` degrees of freedom.
+    pub p_values: Vec<f64>,
+    /// Standard errors clustered by entity, robust to heteroskedasticity
+    /// and to any correlation within an entity over time.
+    pub clustered_std_errors: Vec<f64>,
+    /// Two-sided p-values from the clustered standard errors with
+    /// `n_entities - 1` degrees of freedom.
+    pub clustered_p_values: Vec<f64>,
+    /// Share of the within-entity variation in y explained by the slopes.
+    pub r_squared_within: f64,
+    /// Standard deviation of the idiosyncratic error.
+    pub sigma_e: f64,
+    /// Standard deviation of the entity effect; random effects only.
+    pub sigma_u: Option<f64>,
+    pub n: usize,
+    pub n_entities: usize,
+    pub n_periods: usize,
+    pub df_resid: usize,
+}
+
+/// Regress `y` on the regressor `columns` over a long-format panel where
+/// row i belongs to entity `entities[i]` in year `years[i]`.
+///
+/// With `time_effects`, a dummy for every year but the first absorbs
+/// shocks common to all entities. Unbalanced panels are fine; entities
+/// observed once contribute nothing to fixed-effects slopes.
+pub fn panel_regression(
+    entities: &[u32],
+    years: &[f64],
+    y: &[f64],
+    columns: &[Vec<f64>],
+    model: PanelModel,
+    time_effects: bool,
+) -> Result<PanelFit, ComputeError> {
+    let n = y.len();
+    let entities: Vec<f64> = entities.iter().map(|&e| f64::from(e)).collect();
+    require_same_len("entities", &entities, n)?;
+    require_same_len("years", years, n)?;
+    for column in columns {
+        require_same_len("x", column, n)?;
+    }
+    let (entity, n_entities) = index(&entities);
+    let (period, n_periods) = index(years);
+    if n_entities < 2 {
+        return Err(ComputeError::TooShort {
+            arg: "entities",
+            min: 2,
+            len: n_entities,
+        });
+    }
+
+    // Regressors followed by the time dummies, if any.
+    let k = columns.len();
+    let mut regressors: Vec<Vec<f64>> = columns.to_vec();
+    if time_effects {
+        regressors.extend((1..n_periods).map(|t| {
+            period
+                .iter()
+                .map(|&p| if p == t { 1.0 } else { 0.0 })
+                .collect()
+        }));
+    }
+
+    let counts = entity.iter().fold(vec![0usize; n_entities], |mut c, &g| {
+        c[g] += 1;
+        c
+    });
+    let y_means = group_means(y, &entity, &counts);
+    let x_means: Vec<Vec<f64>> = regressors
+        .iter()
+        .map(|c| group_means(c, &entity, &counts))
+        .collect();
+    let demean = |v: &[f64], means: &[f64], theta: &dyn Fn(usize) -> f64| -> Vec<f64> {
+        v.iter()
+            .zip(&entity)
+            .map(|(v, &g)| v - theta(g) * means[g])
+            .collect()
+    };
+
+    let within_y = demean(y, &y_means, &|_| 1.0);
+    let within_x: Vec<Vec<f64>> = regressors
+        .iter()
+        .zip(&x_means)
+        .map(|(c, m)| demean(c, m, &|_| 1.0))
+        .collect();
+    let within_refs: Vec<&[f64]> = within_x.iter().map(Vec::as_slice).collect();
+    let within_matrix = Matrix::from_columns(n, &within_refs, false);
+    let within_df = n
+        .checked_sub(n_entities + regressors.len())
+        .filter(|&df| df > 0)
+        .ok_or(ComputeError::TooShort {
+            arg: "y",
+            min: n_entities + regressors.len() + 1,
+            len: n,
+        })?;
+    let within =
+        linalg::lstsq(&within_matrix, &within_y).ok_or(ComputeError::Singular { arg: "x" })?;
+    let sigma2_e = within.sse / within_df as f64;
+
+    let (x, target, sigma_u, df_resid) = match model {
+        PanelModel::FixedEffects => (within_matrix, within_y.clone(), None, within_df),
+        PanelModel::RandomEffects => {
+            // Between regression on entity means gives the total variance
+            // of an entity mean; the entity-effect variance is what is
+            // left after the idiosyncratic share. Time dummies are left
+            // out: in a balanced panel their means are the same constant
+            // for every entity.
+            let between_refs: Vec<&[f64]> = x_means[..k].iter().map(Vec::as_slice).collect();
+            let between_df = n_entities.checked_sub(k + 1).filter(|&df| df > 0).ok_or(
+                ComputeError::TooShort {
+                    arg: "entities",
+                    min: k + 2,
+                    len: n_entities,
+                },
+            )?;
+            let between = linalg::lstsq(
+                &Matrix::from_columns(n_entities, &between_refs, true),
+                &y_means,
+            )
+            .ok_or(ComputeError::Singular { arg: "x" })?;
+            let harmonic_t =
+                n_entities as f64 / counts.iter().map(|&c| 1.0 / c as f64).sum::<f64>();
+            let sigma2_u = (between.sse / between_df as f64 - sigma2_e / harmonic_t).max(0.0);
+            let theta =
+                |g: usize| 1.0 - (sigma2_e / (counts[g] as f64 * sigma2_u + sigma2_e)).sqrt();
+
+            let mut transformed =
+                vec![entity.iter().map(|&g| 1.0 - theta(g)).collect::<Vec<f64>>()];
+            transformed.extend(
+                regressors
+                    .iter()
+                    .zip(&x_means)
+                    .map(|(c, m)| demean(c, m, &theta)),
+            );
+            let refs: Vec<&[f64]> = transformed.iter().map(Vec::as_slice).collect();
+            let df =
+                n.checked_sub(refs.len())
+                    .filter(|&df| df > 0)
+                    .ok_or(ComputeError::TooShort {
+                        arg: "y",
+                        min: refs.len() + 1,
+                        len: n,
+                    })?;
+            (
+                Matrix::from_columns(n, &refs, false),
+                demean(y, &y_means, &theta),
+                Some(sigma2_u.sqrt()),
+                df,
+            )
+        }
+    };
+
+    let fit = linalg::lstsq(&x, &target).ok_or(ComputeError::Singular { arg: "x" })?;
+    let xtx_inv = x
+        .gram()
+        .invert()
+        .ok_or(ComputeError::Singular { arg: "x" })?;
+    let cols = x.cols();
+    let sigma2 = fit.sse / df_resid as f64;
+
+    // Cluster-robust covariance sums the score x_i e_i within each entity
+    // before taking outer products, with the usual
+    // G/(G-1) * (N-1)/(N-K) small-sample factor.
+    let mut scores = vec![vec![0.0; cols]; n_entities];
+    for (i, e) in fit.residuals.iter().enumerate() {
+        for (s, x) in scores[entity[i]].iter_mut().zip(x.row(i)) {
+            *s += x * e;
+        }
+    }
+    let g = n_entities as f64;
+    let scale = g / (g - 1.0) * (n as f64 - 1.0) / (n - cols) as f64;
+    let mut clustered = vec![0.0; cols];
+    for score in &scores {
+        let a = xtx_inv.mul_vec(score);
+        for (c, a) in clustered.iter_mut().zip(&a) {
+            *c += a * a;
+        }
+    }
+
+    let reported = k + usize::from(model == PanelModel::RandomEffects);
+    let coefficients = fit.coef[..reported].to_vec();
+    let std_errors: Vec<f64> = (0..reported)
+        .map(|j| (sigma2 * xtx_inv[(j, j)]).sqrt())
+        .collect();
+    let clustered_std_errors: Vec<f64> = clustered[..reported]
+        .iter()
+        .map(|v| (v * scale).sqrt())
+        .collect();
+    let p_values = p_values_for(&coefficients, &std_errors, df_resid as f64);
+    let clustered_p_values = p_values_for(&coefficients, &clustered_std_errors, g - 1.0);
+
+    // Within R² applies the slopes (time effects included) to the
+    // entity-demeaned data, so it is comparable across both models.
+    let slopes = &fit.coef[fit.coef.len() - regressors.len()..];
+    let within_fitted = Matrix::from_columns(n, &within_refs, false).mul_vec(slopes);
+    let within_sse: f64 = within_y
+        .iter()
+        .zip(&within_fitted)
+        .map(|(y, f)| (y - f).powi(2))
+        .sum();
+    let within_tss: f64 = within_y.iter().map(|y| y * y).sum();
+    let r_squared_within = if within_tss == 0.0 {
+        f64::NAN
+    } else {
+        1.0 - within_sse / within_tss
+    };
+
+    Ok(PanelFit {
+        coefficients,
+        std_errors,
+        p_values,
+        clustered_std_errors,
+        clustered_p_values,
+        r_squared_within,
+        sigma_e: sigma2_e.sqrt(),
+        sigma_u,
+        n,
+        n_entities,
+        n_periods,
+        df_resid,
+    })
+}
+
+/// Map each value to the rank of its distinct value, returning the
+/// indices and the number of distinct values.
+fn index(values: &[f64]) -> (Vec<usize>, usize) {
+    let mut distinct = values.to_vec();
+    distinct.sort_by(f64::total_cmp);
+    distinct.dedup();
+    let indices = values
+        .iter()
+        .map(|v| distinct.partition_point(|d| d < v))
+        .collect();
+    (indices, distinct.len())
+}
+
+fn group_means(values: &[f64], groups: &[usize], counts: &[usize]) -> Vec<f64> {
+    let mut sums = vec![0.0; counts.len()];
+    for (v, &g) in values.iter().zip(groups) {
+        sums[g] += v;
+    }
+    sums.iter()
+        .zip(counts)
+        .map(|(s, &c)| s / c as f64)
+        .collect()
+}
+
+fn p_values_for(coefficients: &[f64], std_errors: &[f64], df: f64) -> Vec<f64> {
+    coefficients
+        .iter()
+        .zip(std_errors)
+        .map(|(b, se)| dist::student_t_two_sided_p(b / se, df))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linalg::LeastSquares;
+    use crate::testing::assert_close;
+
+    // Three geographies over four years, with ids that are neither
+    // contiguous nor sorted.
+    const ENTITIES: [u32; 12] = [30, 30, 30, 30, 10, 10, 10, 10, 20, 20, 20, 20];
+    const X: [f64; 12] = [1.0, 2.0, 4.0, 5.0, 3.0, 3.5, 6.0, 8.0, 2.0, 5.0, 5.5, 9.0];
+    const Y: [f64; 12] = [
+        3.1, 4.9, 9.2, 10.8, 8.0, 9.1, 13.5, 17.2, 4.2, 10.5, 11.1, 19.4,
+    ];
+
+    fn years() -> Vec<f64> {
+        (0..12).map(|i| 2000.0 + (i % 4) as f64).collect()
+    }
+
+    fn dummies(keys: &[f64]) -> Vec<Vec<f64>> {
+        let (index, count) = index(keys);
+        (1..count)
+            .map(|d| index.iter().map(|&i| f64::from(u8::from(i == d))).collect())
+            .collect()
+    }
+
+    fn fit(y: &[f64], model: PanelModel, time_effects: bool) -> PanelFit {
+        panel_regression(&ENTITIES, &years(), y, &[X.to_vec()], model, time_effects).unwrap()
+    }
+
+    /// OLS of `y` on an intercept, `X` and the `extra` columns.
+    fn ols(y: &[f64], extra: &[Vec<f64>]) -> LeastSquares {
+        let mut columns: Vec<&[f64]> = vec![&X];
+        columns.extend(extra.iter().map(Vec::as_slice));
+        linalg::lstsq(&Matrix::from_columns(12, &columns, true), y).unwrap()
+    }
+
+    #[test]
+    fn fixed_effects_match_dummy_variable_regression() {
+        let entities: Vec<f64> = ENTITIES.iter().map(|&e| f64::from(e)).collect();
+        let lsdv = ols(&Y, &dummies(&entities));
+        let fe = fit(&Y, PanelModel::FixedEffects, false);
+        assert_close(fe.coefficients[0], lsdv.coef[1], 1e-12);
+        assert_close(fe.coefficients[0], 2.02014742014742, 1e-12);
+        // Same residuals and degrees of freedom as the dummy regression.
+        assert_eq!(fe.df_resid, 8);
+        assert_close(fe.sigma_e, (lsdv.sse / 8.0).sqrt(), 1e-12);
+        assert_close(fe.std_errors[0], 0.0640436908290705, 1e-10);
+        assert_close(fe.r_squared_within, 0.9920237522368537, 1e-12);
+        assert_eq!(fe.sigma_u, None);
+        assert_eq!((fe.n, fe.n_entities, fe.n_periods), (12, 3, 4));
+    }
+
+    #[test]
+    fn time_effects_match_two_way_dummy_regression() {
+        let entities: Vec<f64> = ENTITIES.iter().map(|&e| f64::from(e)).collect();
+        let mut extra = dummies(&entities);
+        extra.extend(dummies(&years()));
+        let lsdv = ols(&Y, &extra);
+        let fe = fit(&Y, PanelModel::FixedEffects, true);
+        assert_close(fe.coefficients[0], lsdv.coef[1], 1e-10);
+        assert_eq!(fe.coefficients.len(), 1);
+        assert_eq!(fe.df_resid, 12 - 3 - 1 - 3);
+        assert_close(fe.sigma_e, (lsdv.sse / 5.0).sqrt(), 1e-10);
+    }
+
+    #[test]
+    fn clustered_errors_match_sandwich() {
+        // (Σx̃²)⁻¹ Σ_g (Σ_{i∈g} x̃ᵢ eᵢ)² (Σx̃²)⁻¹ on the entity-demeaned
+        // data, scaled by G/(G-1) · (N-1)/(N-K) = 3/2 · 11/11.
+        let fe = fit(&Y, PanelModel::FixedEffects, false);
+        assert_close(fe.clustered_std_errors[0], 0.11929077702779944, 1e-10);
+        let t = fe.coefficients[0] / fe.clustered_std_errors[0];
+        assert_close(
+            fe.clustered_p_values[0],
+            dist::student_t_two_sided_p(t, 2.0),
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn random_effects_quasi_demean_by_theta() {
+        // σ²_u from the between regression less σ²_e / T, then
+        // θ = 1 - sqrt(σ²_e / (T σ²_u + σ²_e)) = 0.7211931661248809.
+        let re = fit(&Y, PanelModel::RandomEffects, false);
+        assert_close(re.sigma_e, 0.45680259608895807, 1e-12);
+        assert_close(re.sigma_u.unwrap(), 0.7867258652877501, 1e-10);
+        assert_close(re.coefficients[0], 0.9933401778459201, 1e-10);
+        assert_close(re.coefficients[1], 2.0199984789972025, 1e-10);
+        assert_eq!(re.df_resid, 10);
+    }
+
+    #[test]
+    fn random_effects_collapse_to_pooled_ols_without_entity_variance() {
+        // Errors average to zero within each entity, so the entity means
+        // lie exactly on the line and the between variance is zero.
+        let noise = [
+            0.3, -0.1, -0.4, 0.2, -0.2, 0.5, 0.1, -0.4, 0.1, 0.1, -0.3, 0.1,
+        ];
+        let y: Vec<f64> = X
+            .iter()
+            .zip(noise)
+            .map(|(x, e)| 1.0 + 2.0 * x + e)
+            .collect();
+        let re = fit(&y, PanelModel::RandomEffects, false);
+        let pooled = ols(&y, &[]);
+        assert_eq!(re.sigma_u, Some(0.0));
+        assert_close(re.coefficients[0], pooled.coef[0], 1e-10);
+        assert_close(re.coefficients[1], pooled.coef[1], 1e-10);
+    }
+
+    #[test]
+    fn needs_two_entities() {
+        let err = panel_regression(
+            &[1; 4],
+            &years()[..4],
+            &Y[..4],
+            &[X[..4].to_vec()],
+            PanelModel::FixedEffects,
+            false,
+        );
+        assert!(matches!(
+            err,
+            Err(ComputeError::TooShort {
+                arg: "entities",
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,140 @@
+use serde::Serialize;
+
+use crate::error::{require_len, require_same_len, ComputeError};
+
+/// Result of an ordinary least-squares fit of `y = intercept + slope * x`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LinearFit {
+    pub slope: f64,
+    pub intercept: f64,
+    pub r_squared: f64,
+    /// Standard error of the slope estimate. NaN when n < 3.
+    pub slope_std_error: f64,
+    /// Observed minus fitted value, in input order.
+    pub residuals: Vec<f64>,
+    pub n: usize,
+}
+
+impl LinearFit {
+    /// Fitted value at `x`.
+    pub fn predict(&self, x: f64) -> f64 {
+        self.intercept + self.slope * x
+    }
+}
+
+/// Fit a straight line through `(x[i], y[i])` pairs.
+///
+/// Unlike `slope`, the x axis is taken as given, so gaps between years are
+/// respected. `r_squared` is NaN when every y is equal.
+pub fn linear_fit(x: &[f64], y: &[f64]) -> Result<LinearFit, ComputeError> {
+    require_len("values", y, 2)?;
+    require_same_len("years", x, y.len())?;
+    let n = y.len();
+
+    let n_f = n as f64;
+    let x_mean = x.iter().sum::<f64>() / n_f;
+    let y_mean = y.iter().sum::<f64>() / n_f;
+
+    let mut sxx = 0.0;
+    let mut sxy = 0.0;
+    let mut syy = 0.0;
+    for (&xi, &yi) in x.iter().zip(y) {
+        let dx = xi - x_mean;
+        let dy = yi - y_mean;
+        sxx += dx * dx;
+        sxy += dx * dy;
+        syy += dy * dy;
+    }
+    if sxx == 0.0 {
+        return Err(ComputeError::ZeroVariance { arg: "years" });
+    }
+
+    let slope = sxy / sxx;
+    let intercept = y_mean - slope * x_mean;
+    let residuals: Vec<f64> = x
+        .iter()
+        .zip(y)
+        .map(|(&xi, &yi)| yi - (intercept + slope * xi))
+        .collect();
+    let sse: f64 = residuals.iter().map(|r| r * r).sum();
+
+    let r_squared = if syy == 0.0 {
+        f64::NAN
+    } else {
+        1.0 - sse / syy
+    };
+    let slope_std_error = if n > 2 {
+        (sse / (n_f - 2.0) / sxx).sqrt()
+    } else {
+        f64::NAN
+    };
+
+    Ok(LinearFit {
+        slope,
+        intercept,
+        r_squared,
+        slope_std_error,
+        residuals,
+        n,
+    })
+}
+
+/// Slope of `values` against their index positions 0..n.
+pub fn slope(values: &[f64]) -> Result<f64, ComputeError> {
+    let index: Vec<f64> = (0..values.len()).map(|i| i as f64).collect();
+    Ok(linear_fit(&index, values)?.slope)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    #[test]
+    fn fits_gappy_years_on_the_year_axis() {
+        let fit = linear_fit(&[2011.0, 2014.0, 2019.0], &[10.0, 14.0, 19.0]).unwrap();
+        assert_eq!(fit.n, 3);
+        assert_close(fit.slope, 109.0 / 98.0, 1e-12);
+        assert_close(fit.intercept, -2_226.469_387_755_102, 1e-12);
+        assert_close(fit.r_squared, 0.993_726_998_996_319_9, 1e-12);
+        assert_close(fit.slope_std_error, 0.088_369_939_161_677_41, 1e-10);
+        let residuals = [
+            -0.255_102_040_816_326_5,
+            0.408_163_265_306_122_5,
+            -0.153_061_224_489_795_9,
+        ];
+        for (&r, e) in fit.residuals.iter().zip(residuals) {
+            assert_close(r, e, 1e-9);
+        }
+        assert_close(fit.predict(2014.0), 13.591_836_734_693_877, 1e-12);
+    }
+
+    #[test]
+    fn degenerate_inputs_are_errors() {
+        let two = linear_fit(&[2000.0, 2004.0], &[1.0, 3.0]).unwrap();
+        assert_close(two.slope, 0.5, 1e-15);
+        assert!(two.slope_std_error.is_nan());
+        assert_eq!(
+            linear_fit(&[2000.0], &[1.0]).unwrap_err(),
+            ComputeError::TooShort {
+                arg: "values",
+                min: 2,
+                len: 1
+            }
+        );
+        assert_eq!(
+            linear_fit(&[2000.0, 2001.0, 2002.0], &[1.0, 2.0]).unwrap_err(),
+            ComputeError::LengthMismatch {
+                arg: "years",
+                expected: 2,
+                len: 3
+            }
+        );
+        assert_eq!(
+            linear_fit(&[2000.0, 2000.0], &[1.0, 2.0]).unwrap_err(),
+            ComputeError::ZeroVariance { arg: "years" }
+        );
+        let flat = linear_fit(&[2000.0, 2001.0], &[4.0, 4.0]).unwrap();
+        assert!(flat.r_squared.is_nan());
+    }
+}
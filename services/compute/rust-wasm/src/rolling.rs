@@ -0,0 +1,175 @@
+use serde::Serialize;
+
+use crate::align::Aligned;
+use crate::error::{require_range, ComputeError};
+use crate::series::YearSeries;
+use crate::{correlation, regression, stats};
+
+/// One point of a rolling statistic, dated at the last year of its window.
+#[derive(Debug, Clone, Serialize)]
+pub struct RollingPoint {
+    pub year: f64,
+    /// `None` when the window holds fewer than `min_periods` observations
+    /// or the statistic is undefined for them (e.g. zero variance).
+    pub value: Option<f64>,
+    /// Observations inside the window.
+    pub n: usize,
+}
+
+/// Statistic computed over each rolling window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollingStat {
+    Mean,
+    StdDev,
+    Slope,
+}
+
+/// Apply `stat` over a trailing window of `window` years ending at each
+/// observed year: the window for year t covers (t - window, t]. Gaps in
+/// the series simply leave fewer observations in the affected windows.
+pub fn rolling(
+    series: &YearSeries,
+    window: usize,
+    min_periods: usize,
+    stat: RollingStat,
+) -> Result<Vec<RollingPoint>, ComputeError> {
+    check_window(window, min_periods)?;
+    let years = series.years();
+    let values = series.values();
+    Ok(windows(years, window)
+        .map(|(year, range)| {
+            let (ys, vs) = (&years[range.clone()], &values[range]);
+            let value = (vs.len() >= min_periods)
+                .then(|| match stat {
+                    RollingStat::Mean => stats::mean(vs),
+                    RollingStat::StdDev => stats::std_dev(vs),
+                    RollingStat::Slope => regression::linear_fit(ys, vs).map(|f| f.slope),
+                })
+                .and_then(Result::ok);
+            RollingPoint {
+                year,
+                value,
+                n: vs.len(),
+            }
+        })
+        .collect())
+}
+
+/// Rolling Pearson correlation of two series already aligned on year,
+/// over trailing windows as in `rolling`.
+pub fn rolling_pearson(
+    aligned: &Aligned,
+    window: usize,
+    min_periods: usize,
+) -> Result<Vec<RollingPoint>, ComputeError> {
+    check_window(window, min_periods)?;
+    Ok(windows(&aligned.years, window)
+        .map(|(year, range)| {
+            let (a, b) = (&aligned.a[range.clone()], &aligned.b[range]);
+            let value = (a.len() >= min_periods)
+                .then(|| correlation::pearson(a, b).ok())
+                .flatten();
+            RollingPoint {
+                year,
+                value,
+                n: a.len(),
+            }
+        })
+        .collect())
+}
+
+fn check_window(window: usize, min_periods: usize) -> Result<(), ComputeError> {
+    require_range("window", window as f64, 1.0, f64::INFINITY)?;
+    require_range("min_periods", min_periods as f64, 1.0, window as f64)
+}
+
+/// For each year in ascending `years`, the index range of the years that
+/// fall in the trailing window ending there.
+fn windows(
+    years: &[f64],
+    window: usize,
+) -> impl Iterator<Item = (f64, std::ops::Range<usize>)> + '_ {
+    let span = window as f64;
+    let mut start = 0;
+    years.iter().enumerate().map(move |(end, &year)| {
+        while years[start] <= year - span {
+            start += 1;
+        }
+        (year, start..end + 1)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::align::{align, Join};
+    use crate::testing::assert_close;
+
+    fn gappy() -> YearSeries {
+        YearSeries::new(
+            &[2000.0, 2001.0, 2002.0, 2004.0, 2005.0, 2008.0],
+            &[1.0, 3.0, 5.0, 4.0, 8.0, 10.0],
+        )
+    }
+
+    fn values(points: &[RollingPoint]) -> Vec<Option<f64>> {
+        points.iter().map(|p| p.value).collect()
+    }
+
+    #[test]
+    fn windows_cover_trailing_years() {
+        let points = rolling(&gappy(), 3, 2, RollingStat::Mean).unwrap();
+        let years: Vec<f64> = points.iter().map(|p| p.year).collect();
+        assert_eq!(years, [2000.0, 2001.0, 2002.0, 2004.0, 2005.0, 2008.0]);
+        // (t - 3, t]: 2004 sees 2002 and 2004; 2008 sees only itself.
+        let n: Vec<usize> = points.iter().map(|p| p.n).collect();
+        assert_eq!(n, [1, 2, 3, 2, 2, 1]);
+        assert_eq!(
+            values(&points),
+            [None, Some(2.0), Some(3.0), Some(4.5), Some(6.0), None]
+        );
+    }
+
+    #[test]
+    fn min_periods_gates_short_windows() {
+        let points = rolling(&gappy(), 3, 1, RollingStat::Mean).unwrap();
+        assert_eq!(points[0].value, Some(1.0));
+        assert_eq!(points[5].value, Some(10.0));
+        let points = rolling(&gappy(), 3, 3, RollingStat::Mean).unwrap();
+        assert_eq!(values(&points), [None, None, Some(3.0), None, None, None]);
+    }
+
+    #[test]
+    fn undefined_statistics_are_none() {
+        // One observation has no spread and no slope, whatever min_periods.
+        let sd = rolling(&gappy(), 3, 1, RollingStat::StdDev).unwrap();
+        assert_eq!(sd[0].value, None);
+        assert_close(sd[1].value.unwrap(), 2.0f64.sqrt(), 1e-12);
+        let slope = rolling(&gappy(), 3, 1, RollingStat::Slope).unwrap();
+        assert_eq!(slope[0].value, None);
+        assert_close(slope[2].value.unwrap(), 2.0, 1e-12);
+        assert_close(slope[3].value.unwrap(), -0.5, 1e-12);
+        assert_eq!(slope[5].value, None);
+    }
+
+    #[test]
+    fn rolling_pearson_skips_flat_windows() {
+        let b = YearSeries::new(
+            &[2000.0, 2001.0, 2002.0, 2004.0, 2005.0, 2008.0],
+            &[2.0, 2.0, 2.0, 1.0, 6.0, 7.0],
+        );
+        let aligned = align(&gappy(), &b, Join::Inner);
+        let points = rolling_pearson(&aligned, 4, 2).unwrap();
+        // 2001 pairs two points with a flat `b`; 2005 sees 2002, 2004, 2005.
+        assert_eq!(points[1].value, None);
+        assert_close(points[4].value.unwrap(), 0.998_625_428_903_524, 1e-12);
+    }
+
+    #[test]
+    fn rejects_bad_windows() {
+        for (window, min_periods) in [(0, 1), (3, 0), (3, 4)] {
+            let err = rolling(&gappy(), window, min_periods, RollingStat::Mean).unwrap_err();
+            assert_eq!(err.code(), "OUT_OF_RANGE");
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::error::{require_same_len, ComputeError};
+
+/// An indicator series keyed by year, sorted ascending with unique years.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct YearSeries {
+    years: Vec<f64>,
+    values: Vec<f64>,
+}
+
+impl YearSeries {
+    /// Build a series from parallel year/value slices.
+    ///
+    /// Pairs beyond the shorter slice are ignored. Points are sorted by
+    /// year; if a year appears more than once, the last value wins.
+    pub fn new(years: &[f64], values: &[f64]) -> Self {
+        let n = years.len().min(values.len());
+        let mut points: Vec<(f64, f64)> = years[..n]
+            .iter()
+            .copied()
+            .zip(values[..n].iter().copied())
+            .collect();
+        // Stable sort keeps input order within a year so "last wins" holds.
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut series = YearSeries {
+            years: Vec::with_capacity(n),
+            values: Vec::with_capacity(n),
+        };
+        for (year, value) in points {
+            if series.years.last() == Some(&year) {
+                *series.values.last_mut().unwrap() = value;
+            } else {
+                series.years.push(year);
+                series.values.push(value);
+            }
+        }
+        series
+    }
+
+    /// Like `new`, but rejects year/value slices of different lengths.
+    /// `arg` names the years argument in the error.
+    pub fn from_arrays(
+        arg: &'static str,
+        years: &[f64],
+        values: &[f64],
+    ) -> Result<Self, ComputeError> {
+        require_same_len(arg, years, values.len())?;
+        Ok(YearSeries::new(years, values))
+    }
+
+    pub fn years(&self) -> &[f64] {
+        &self.years
+    }
+
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    pub fn len(&self) -> usize {
+        self.years.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.years.is_empty()
+    }
+
+    /// Value observed at exactly `year`, if any.
+    pub fn get(&self, year: f64) -> Option<f64> {
+        self.years
+            .binary_search_by(|y| y.total_cmp(&year))
+            .ok()
+            .map(|i| self.values[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_by_year_and_last_duplicate_wins() {
+        let series = YearSeries::new(
+            &[2010.0, 2001.0, 2005.0, 2001.0, 1999.0],
+            &[5.0, 1.0, 3.0, 2.0, 0.0, 99.0],
+        );
+        assert_eq!(series.years(), &[1999.0, 2001.0, 2005.0, 2010.0]);
+        assert_eq!(series.values(), &[0.0, 2.0, 3.0, 5.0]);
+        assert_eq!(series.len(), 4);
+    }
+
+    #[test]
+    fn get_matches_exact_years_only() {
+        let series = YearSeries::new(&[2000.0, 2004.0], &[1.0, 2.0]);
+        assert_eq!(series.get(2004.0), Some(2.0));
+        assert_eq!(series.get(2002.0), None);
+        assert!(YearSeries::new(&[], &[]).is_empty());
+    }
+}
@@ -0,0 +1,469 @@
+//! Unit-root, stationarity and cointegration tests for evenly spaced
+//! series.
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::align::Aligned;
+use crate::criteria::{gaussian_log_lik, InformationCriterion};
+use crate::error::{require_len, require_range, require_regular, ComputeError};
+use crate::linalg::{self, Matrix};
+use crate::series::YearSeries;
+
+/// Fewest observations any test here accepts.
+const MIN_OBS: usize = 8;
+
+/// MacKinnon (2010) response surfaces for Dickey–Fuller critical values:
+/// `[b0, b1, b2, b3]` giving `b0 + b1/T + b2/T² + b3/T³` at 1%, 5% and 10%,
+/// for one series (ADF) and two (Engle–Granger).
+const TAU_CONSTANT: [[[f64; 4]; 3]; 2] = [
+    [
+        [-3.43035, -6.5393, -16.786, -79.433],
+        [-2.86154, -2.8903, -4.234, -40.040],
+        [-2.56677, -1.5384, -2.809, 0.0],
+    ],
+    [
+        [-3.89644, -10.9519, -33.527, 0.0],
+        [-3.33613, -6.1101, -6.823, 0.0],
+        [-3.04445, -4.2412, -2.720, 0.0],
+    ],
+];
+const TAU_TREND: [[[f64; 4]; 3]; 2] = [
+    [
+        [-3.95877, -9.0531, -28.428, -134.155],
+        [-3.41049, -4.3904, -9.036, -45.374],
+        [-3.12705, -2.5856, -3.925, -22.380],
+    ],
+    [
+        [-4.32762, -15.4387, -35.679, 0.0],
+        [-3.78057, -9.5106, -12.074, 0.0],
+        [-3.49631, -7.0815, -7.538, 21.892],
+    ],
+];
+
+/// Kwiatkowski et al. (1992) asymptotic critical values at 10%, 5%, 2.5%
+/// and 1%.
+const KPSS_P: [f64; 4] = [0.10, 0.05, 0.025, 0.01];
+const KPSS_CONSTANT: [f64; 4] = [0.347, 0.463, 0.574, 0.739];
+const KPSS_TREND: [f64; 4] = [0.119, 0.146, 0.176, 0.216];
+
+/// Deterministic terms included in a test regression.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Deterministic {
+    /// Intercept only: stationary around a constant level.
+    #[default]
+    Constant = 0,
+    /// Intercept and linear time trend: stationary around a trend.
+    Trend = 1,
+}
+
+impl Deterministic {
+    fn terms(self) -> usize {
+        match self {
+            Deterministic::Constant => 1,
+            Deterministic::Trend => 2,
+        }
+    }
+}
+
+/// Critical values of a test statistic at conventional levels.
+#[derive(Debug, Clone, Serialize)]
+pub struct CriticalValues {
+    pub pct1: f64,
+    pub pct5: f64,
+    pub pct10: f64,
+}
+
+/// Augmented Dickey–Fuller test; the null hypothesis is a unit root.
+#[derive(Debug, Clone, Serialize)]
+pub struct AdfTest {
+    /// t statistic of the lagged level; more negative is stronger
+    /// evidence against a unit root.
+    pub statistic: f64,
+    /// Lagged differences included.
+    pub lags: usize,
+    /// Observations in the test regression.
+    pub n: usize,
+    /// MacKinnon finite-sample critical values for `n`.
+    pub critical_values: CriticalValues,
+    /// Whether the unit root is rejected at 5%.
+    pub stationary: bool,
+}
+
+/// KPSS test; the null hypothesis is stationarity.
+#[derive(Debug, Clone, Serialize)]
+pub struct KpssTest {
+    pub statistic: f64,
+    /// Bartlett-window lags in the long-run variance.
+    pub lags: usize,
+    pub n: usize,
+    /// Interpolated from the KPSS table, so only resolved between 0.01
+    /// and 0.10; values outside are clamped to those bounds.
+    pub p_value: f64,
+    pub critical_values: CriticalValues,
+    /// Whether stationarity survives at 5%.
+    pub stationary: bool,
+}
+
+/// Engle–Granger two-step cointegration test of `a` on `b`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CointegrationTest {
+    /// Cointegrating regression `a = intercept + slope * b` (plus a trend
+    /// term when requested).
+    pub intercept: f64,
+    pub slope: f64,
+    /// ADF statistic on the regression residuals.
+    pub statistic: f64,
+    pub lags: usize,
+    pub n: usize,
+    /// MacKinnon two-variable critical values, which are further out than
+    /// the one-series ADF ones because the residuals are estimated.
+    pub critical_values: CriticalValues,
+    /// Whether the no-cointegration null is rejected at 5%.
+    pub cointegrated: bool,
+}
+
+/// Augmented Dickey–Fuller test on an evenly spaced series.
+///
+/// The number of lagged differences is chosen by `criterion` between 0
+/// and `max_lag` on a common sample, then the test regression is refitted
+/// with all usable observations. `max_lag` defaults to Schwert's
+/// `12 (n / 100)^(1/4)`, capped so the regression keeps residual degrees
+/// of freedom.
+pub fn adf(
+    series: &YearSeries,
+    deterministic: Deterministic,
+    max_lag: Option<usize>,
+    criterion: InformationCriterion,
+) -> Result<AdfTest, ComputeError> {
+    require_len("values", series.values(), MIN_OBS)?;
+    require_regular("years", series.years())?;
+    let values = series.values();
+    let (statistic, lags, n) = adf_statistic(values, deterministic.terms(), max_lag, criterion)?;
+    let critical_values = mackinnon_critical(1, deterministic, n);
+    Ok(AdfTest {
+        statistic,
+        lags,
+        n,
+        stationary: statistic < critical_values.pct5,
+        critical_values,
+    })
+}
+
+/// KPSS test on an evenly spaced series.
+///
+/// `lags` for the Bartlett long-run variance defaults to
+/// `4 (n / 100)^(1/4)`, the shorter of the two rules in the original
+/// paper, which suits annual series of a few decades.
+pub fn kpss(
+    series: &YearSeries,
+    deterministic: Deterministic,
+    lags: Option<usize>,
+) -> Result<KpssTest, ComputeError> {
+    require_regular("years", series.years())?;
+    kpss_values(series.values(), deterministic, lags)
+}
+
+/// `kpss` on values already known to be evenly spaced.
+pub(crate) fn kpss_values(
+    values: &[f64],
+    deterministic: Deterministic,
+    lags: Option<usize>,
+) -> Result<KpssTest, ComputeError> {
+    require_len("values", values, MIN_OBS)?;
+    let n = values.len();
+    let lags = match lags {
+        Some(lags) => {
+            require_range("lags", lags as f64, 0.0, (n - 1) as f64)?;
+            lags
+        }
+        None => (4.0 * (n as f64 / 100.0).powf(0.25)) as usize,
+    };
+
+    let fit = linalg::lstsq(&deterministic_matrix(n, deterministic.terms(), 0), values)
+        .ok_or(ComputeError::Singular { arg: "values" })?;
+    let e = &fit.residuals;
+    let n_f = n as f64;
+    let autocov = |s: usize| (s..n).map(|t| e[t] * e[t - s]).sum::<f64>() / n_f;
+    let long_run = autocov(0)
+        + 2.0
+            * (1..=lags)
+                .map(|s| (1.0 - s as f64 / (lags + 1) as f64) * autocov(s))
+                .sum::<f64>();
+    if long_run <= 0.0 {
+        return Err(ComputeError::ZeroVariance { arg: "values" });
+    }
+    let mut partial = 0.0;
+    let statistic = e
+        .iter()
+        .map(|r| {
+            partial += r;
+            partial * partial
+        })
+        .sum::<f64>()
+        / (n_f * n_f * long_run);
+
+    let table = match deterministic {
+        Deterministic::Constant => KPSS_CONSTANT,
+        Deterministic::Trend => KPSS_TREND,
+    };
+    Ok(KpssTest {
+        statistic,
+        lags,
+        n,
+        p_value: kpss_p_value(statistic, &table),
+        critical_values: CriticalValues {
+            pct1: table[3],
+            pct5: table[1],
+            pct10: table[0],
+        },
+        stationary: statistic < table[1],
+    })
+}
+
+/// Engle–Granger cointegration test between two aligned, evenly spaced
+/// series: regress `a` on `b` with the chosen deterministic terms, then
+/// run an ADF test without deterministic terms on the residuals.
+pub fn engle_granger(
+    aligned: &Aligned,
+    deterministic: Deterministic,
+    max_lag: Option<usize>,
+    criterion: InformationCriterion,
+) -> Result<CointegrationTest, ComputeError> {
+    require_len("values", &aligned.a, MIN_OBS)?;
+    require_regular("years", &aligned.years)?;
+    let n = aligned.len();
+    let terms = deterministic.terms();
+
+    let mut x = deterministic_matrix(n, terms, 1);
+    for (i, b) in aligned.b.iter().enumerate() {
+        x[(i, terms)] = *b;
+    }
+    let fit = linalg::lstsq(&x, &aligned.a).ok_or(ComputeError::Singular { arg: "values" })?;
+    let (statistic, lags, used) = adf_statistic(&fit.residuals, 0, max_lag, criterion)?;
+    let critical_values = mackinnon_critical(2, deterministic, used);
+    Ok(CointegrationTest {
+        intercept: fit.coef[0],
+        slope: fit.coef[terms],
+        statistic,
+        lags,
+        n: used,
+        cointegrated: statistic < critical_values.pct5,
+        critical_values,
+    })
+}
+
+/// ADF t statistic with `terms` deterministic columns (0, 1 or 2) and the
+/// lag order chosen by `criterion`. Returns the statistic, lag order and
+/// observations used.
+fn adf_statistic(
+    values: &[f64],
+    terms: usize,
+    max_lag: Option<usize>,
+    criterion: InformationCriterion,
+) -> Result<(f64, usize, usize), ComputeError> {
+    let n = values.len();
+    // Each lag costs a row and a column; cap the order so the longest
+    // regression keeps residual degrees of freedom on short series.
+    let cap = (n.saturating_sub(2 * terms + 3)) / 3;
+    let max_lag = match max_lag {
+        Some(lag) => {
+            require_range("max_lag", lag as f64, 0.0, cap as f64)?;
+            lag
+        }
+        None => ((12.0 * (n as f64 / 100.0).powf(0.25)) as usize).min(cap),
+    };
+
+    let mut best = (0, f64::INFINITY);
+    for lags in 0..=max_lag {
+        let (fit, _) = adf_regression(values, terms, lags, max_lag + 1)?;
+        let rows = fit.residuals.len();
+        let value = criterion.value(gaussian_log_lik(fit.sse, rows), terms + 1 + lags, rows);
+        if value < best.1 {
+            best = (lags, value);
+        }
+    }
+    let lags = best.0;
+    let (fit, se) = adf_regression(values, terms, lags, lags + 1)?;
+    Ok((fit.coef[0] / se, lags, fit.residuals.len()))
+}
+
+/// Regress the difference at `t` on the previous level, `terms`
+/// deterministic columns and `lags` lagged differences, for `t` from
+/// `start` on. The level coefficient comes first; its standard error is
+/// returned alongside, via Frisch–Waugh–Lovell.
+fn adf_regression(
+    values: &[f64],
+    terms: usize,
+    lags: usize,
+    start: usize,
+) -> Result<(linalg::LeastSquares, f64), ComputeError> {
+    let rows = values.len() - start;
+    let cols = 1 + terms + lags;
+    let mut x = Matrix::zeros(rows, cols);
+    let mut others = Matrix::zeros(rows, cols - 1);
+    let mut dy = Vec::with_capacity(rows);
+    for (row, t) in (start..values.len()).enumerate() {
+        dy.push(values[t] - values[t - 1]);
+        x[(row, 0)] = values[t - 1];
+        let mut column = |j: usize, v: f64| {
+            x[(row, j + 1)] = v;
+            others[(row, j)] = v;
+        };
+        if terms > 0 {
+            column(0, 1.0);
+        }
+        if terms > 1 {
+            column(1, t as f64);
+        }
+        for lag in 1..=lags {
+            column(terms + lag - 1, values[t - lag] - values[t - lag - 1]);
+        }
+    }
+    let singular = ComputeError::Singular { arg: "values" };
+    let fit = linalg::lstsq(&x, &dy).ok_or(singular.clone())?;
+    let level: Vec<f64> = (0..rows).map(|i| x[(i, 0)]).collect();
+    let level_sse = if cols == 1 {
+        level.iter().map(|v| v * v).sum()
+    } else {
+        linalg::lstsq(&others, &level).ok_or(singular)?.sse
+    };
+    let sigma2 = fit.sse / (rows - cols) as f64;
+    Ok((fit, (sigma2 / level_sse).sqrt()))
+}
+
+/// Design matrix with an intercept, plus a time index when `terms` is 2,
+/// and `extra` trailing columns of zeros for the caller to fill.
+fn deterministic_matrix(n: usize, terms: usize, extra: usize) -> Matrix {
+    let mut x = Matrix::zeros(n, terms + extra);
+    for i in 0..n {
+        x[(i, 0)] = 1.0;
+        if terms > 1 {
+            x[(i, 1)] = i as f64;
+        }
+    }
+    x
+}
+
+/// MacKinnon critical values for `n_series` series and `n` observations.
+fn mackinnon_critical(n_series: usize, deterministic: Deterministic, n: usize) -> CriticalValues {
+    let table = match deterministic {
+        Deterministic::Constant => &TAU_CONSTANT[n_series - 1],
+        Deterministic::Trend => &TAU_TREND[n_series - 1],
+    };
+    let t = n as f64;
+    let at = |b: &[f64; 4]| b[0] + b[1] / t + b[2] / (t * t) + b[3] / (t * t * t);
+    CriticalValues {
+        pct1: at(&table[0]),
+        pct5: at(&table[1]),
+        pct10: at(&table[2]),
+    }
+}
+
+/// Linear interpolation of the KPSS p-value, clamped to the table's range.
+fn kpss_p_value(statistic: f64, table: &[f64; 4]) -> f64 {
+    if statistic <= table[0] {
+        return KPSS_P[0];
+    }
+    if statistic >= table[3] {
+        return KPSS_P[3];
+    }
+    let i = table.iter().rposition(|&c| c <= statistic).unwrap_or(0);
+    let w = (statistic - table[i]) / (table[i + 1] - table[i]);
+    KPSS_P[i] + w * (KPSS_P[i + 1] - KPSS_P[i])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{annual, assert_close, LH};
+
+    #[test]
+    fn dickey_fuller_statistics() {
+        let series = annual(&LH);
+        let constant = adf(
+            &series,
+            Deterministic::Constant,
+            Some(0),
+            InformationCriterion::Aic,
+        )
+        .unwrap();
+        assert_eq!((constant.lags, constant.n), (0, 47));
+        assert_close(constant.statistic, -3.380_907_309_057_47, 1e-10);
+        let trend = adf(
+            &series,
+            Deterministic::Trend,
+            Some(0),
+            InformationCriterion::Aic,
+        )
+        .unwrap();
+        assert_close(trend.statistic, -3.694_287_718_309_642, 1e-10);
+
+        // One lagged difference, from the third observation on.
+        let (fit, se) = adf_regression(&LH, 1, 1, 2).unwrap();
+        assert_eq!(fit.residuals.len(), 46);
+        assert_close(fit.coef[0] / se, -3.677_745_233_152_081, 1e-10);
+    }
+
+    #[test]
+    fn mackinnon_critical_values() {
+        // statsmodels' adfuller reports these for 100 observations.
+        let c = mackinnon_critical(1, Deterministic::Constant, 100);
+        assert_close(c.pct1, -3.497_501_033, 1e-9);
+        assert_close(c.pct5, -2.890_906_44, 1e-9);
+        assert_close(c.pct10, -2.582_434_9, 1e-9);
+        // Asymptotic values from MacKinnon (2010), tables 1 and 2.
+        let c = mackinnon_critical(1, Deterministic::Trend, usize::MAX);
+        assert_close(c.pct1, -3.95877, 1e-9);
+        assert_close(c.pct5, -3.41049, 1e-9);
+        assert_close(c.pct10, -3.12705, 1e-9);
+        let c = mackinnon_critical(2, Deterministic::Constant, usize::MAX);
+        assert_close(c.pct1, -3.89644, 1e-9);
+        assert_close(c.pct5, -3.33613, 1e-9);
+        assert_close(c.pct10, -3.04445, 1e-9);
+    }
+
+    #[test]
+    fn kpss_statistics_and_table() {
+        let series = annual(&LH);
+        let level = kpss(&series, Deterministic::Constant, Some(3)).unwrap();
+        assert_close(level.statistic, 0.293_815_727_283_469_2, 1e-10);
+        assert_eq!(level.p_value, 0.10);
+        assert!(level.stationary);
+        assert_eq!(
+            (
+                level.critical_values.pct1,
+                level.critical_values.pct5,
+                level.critical_values.pct10
+            ),
+            (0.739, 0.463, 0.347)
+        );
+        let trend = kpss(&series, Deterministic::Trend, Some(3)).unwrap();
+        assert_close(trend.statistic, 0.054_607_444_461_142_89, 1e-10);
+        assert_eq!(trend.critical_values.pct5, 0.146);
+
+        assert_close(kpss_p_value(0.5, &KPSS_CONSTANT), 0.05 - 0.025 / 3.0, 1e-12);
+        assert_eq!(kpss_p_value(2.0, &KPSS_CONSTANT), 0.01);
+    }
+
+    #[test]
+    fn kpss_rejects_level_stationarity_around_a_trend() {
+        let trending: Vec<f64> = LH
+            .iter()
+            .enumerate()
+            .map(|(t, v)| v + 0.05 * t as f64)
+            .collect();
+        let series = annual(&trending);
+        assert!(
+            !kpss(&series, Deterministic::Constant, None)
+                .unwrap()
+                .stationary
+        );
+        assert!(
+            kpss(&series, Deterministic::Trend, None)
+                .unwrap()
+                .stationary
+        );
+    }
+}
@@ -0,0 +1,330 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::{require_len, require_range, ComputeError};
+
+/// Sample quantile definitions 1–9 of Hyndman & Fan (1996), numbered as in
+/// R's `quantile(type = ...)`. `Type7` is the R and NumPy default.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuantileMethod {
+    /// Inverse of the empirical CDF.
+    Type1 = 1,
+    /// Inverse empirical CDF, averaging at discontinuities.
+    Type2 = 2,
+    /// Nearest even order statistic (SAS definition 2).
+    Type3 = 3,
+    /// Linear interpolation of the empirical CDF.
+    Type4 = 4,
+    /// Piecewise linear with knots at the midpoints of the steps.
+    Type5 = 5,
+    /// Linear, `p[k] = k / (n + 1)` (Minitab, SPSS).
+    Type6 = 6,
+    /// Linear, `p[k] = (k - 1) / (n - 1)` (R, NumPy, Excel `PERCENTILE`).
+    #[default]
+    Type7 = 7,
+    /// Approximately median-unbiased regardless of distribution.
+    Type8 = 8,
+    /// Approximately unbiased for normally distributed data.
+    Type9 = 9,
+}
+
+/// Arithmetic mean of `values`.
+pub fn mean(values: &[f64]) -> Result<f64, ComputeError> {
+    require_len("values", values, 1)?;
+    Ok(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Median of `values`. Averages the two middle values for even lengths.
+pub fn median(values: &[f64]) -> Result<f64, ComputeError> {
+    require_len("values", values, 1)?;
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        Ok((sorted[mid - 1] + sorted[mid]) / 2.0)
+    } else {
+        Ok(sorted[mid])
+    }
+}
+
+/// Sum of squared deviations from the mean.
+fn sum_sq_dev(values: &[f64]) -> Result<f64, ComputeError> {
+    let m = mean(values)?;
+    Ok(values.iter().map(|v| (v - m) * (v - m)).sum())
+}
+
+/// Sample variance of `values` (n - 1 denominator).
+pub fn variance(values: &[f64]) -> Result<f64, ComputeError> {
+    require_len("values", values, 2)?;
+    Ok(sum_sq_dev(values)? / (values.len() - 1) as f64)
+}
+
+/// Population variance of `values` (n denominator).
+pub fn population_variance(values: &[f64]) -> Result<f64, ComputeError> {
+    require_len("values", values, 1)?;
+    Ok(sum_sq_dev(values)? / values.len() as f64)
+}
+
+/// Sample standard deviation of `values` (n - 1 denominator).
+pub fn std_dev(values: &[f64]) -> Result<f64, ComputeError> {
+    Ok(variance(values)?.sqrt())
+}
+
+/// Population standard deviation of `values` (n denominator).
+pub fn population_std_dev(values: &[f64]) -> Result<f64, ComputeError> {
+    Ok(population_variance(values)?.sqrt())
+}
+
+/// Sample quantile of `values` at probability `p` in [0, 1].
+pub fn quantile(values: &[f64], p: f64, method: QuantileMethod) -> Result<f64, ComputeError> {
+    require_len("values", values, 1)?;
+    require_range("p", p, 0.0, 1.0)?;
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    Ok(quantile_sorted(&sorted, p, method))
+}
+
+/// Sample quantiles of `values` at each probability in `ps`, sorting once.
+pub fn quantiles(
+    values: &[f64],
+    ps: &[f64],
+    method: QuantileMethod,
+) -> Result<Vec<f64>, ComputeError> {
+    require_len("values", values, 1)?;
+    for &p in ps {
+        require_range("ps", p, 0.0, 1.0)?;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    Ok(ps
+        .iter()
+        .map(|&p| quantile_sorted(&sorted, p, method))
+        .collect())
+}
+
+/// Hyndman & Fan quantile of an ascending, non-empty slice.
+fn quantile_sorted(sorted: &[f64], p: f64, method: QuantileMethod) -> f64 {
+    // Absorbs rounding in n * p so exact order statistics are hit exactly.
+    const FUZZ: f64 = 4.0 * f64::EPSILON;
+
+    let n = sorted.len() as f64;
+    let m = match method {
+        QuantileMethod::Type1 | QuantileMethod::Type2 | QuantileMethod::Type4 => 0.0,
+        QuantileMethod::Type3 => -0.5,
+        QuantileMethod::Type5 => 0.5,
+        QuantileMethod::Type6 => p,
+        QuantileMethod::Type7 => 1.0 - p,
+        QuantileMethod::Type8 => (p + 1.0) / 3.0,
+        QuantileMethod::Type9 => p / 4.0 + 3.0 / 8.0,
+    };
+    let h = n * p + m;
+    let j = (h + FUZZ).floor();
+    let mut g = h - j;
+    if g.abs() < FUZZ {
+        g = 0.0;
+    }
+
+    let gamma = match method {
+        QuantileMethod::Type1 => {
+            if g > 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        QuantileMethod::Type2 => {
+            if g > 0.0 {
+                1.0
+            } else {
+                0.5
+            }
+        }
+        QuantileMethod::Type3 => {
+            if g == 0.0 && j % 2.0 == 0.0 {
+                0.0
+            } else {
+                1.0
+            }
+        }
+        _ => g,
+    };
+
+    // Order statistics are 1-based; clamp x[0] to x[1] and x[n+1] to x[n].
+    let at = |k: f64| sorted[(k.clamp(1.0, n) as usize) - 1];
+    let lo = at(j);
+    let hi = at(j + 1.0);
+    if gamma == 0.0 {
+        lo
+    } else {
+        (1.0 - gamma) * lo + gamma * hi
+    }
+}
+
+/// Interquartile range, `Q3 - Q1`.
+pub fn iqr(values: &[f64], method: QuantileMethod) -> Result<f64, ComputeError> {
+    let q = quantiles(values, &[0.25, 0.75], method)?;
+    Ok(q[1] - q[0])
+}
+
+/// Median absolute deviation from the median, unscaled. Multiply by
+/// 1.4826 for a consistent estimate of the standard deviation under
+/// normality.
+pub fn mad(values: &[f64]) -> Result<f64, ComputeError> {
+    let center = median(values)?;
+    let deviations: Vec<f64> = values.iter().map(|v| (v - center).abs()).collect();
+    median(&deviations)
+}
+
+/// Central moments m2, m3, m4 (n denominator).
+fn central_moments(values: &[f64]) -> Result<(f64, f64, f64), ComputeError> {
+    let m = mean(values)?;
+    let n = values.len() as f64;
+    let (mut m2, mut m3, mut m4) = (0.0, 0.0, 0.0);
+    for &v in values {
+        let d = v - m;
+        let d2 = d * d;
+        m2 += d2;
+        m3 += d2 * d;
+        m4 += d2 * d2;
+    }
+    if m2 == 0.0 {
+        return Err(ComputeError::ZeroVariance { arg: "values" });
+    }
+    Ok((m2 / n, m3 / n, m4 / n))
+}
+
+/// Adjusted Fisher–Pearson sample skewness G1 (as Excel `SKEW` and SAS).
+pub fn skewness(values: &[f64]) -> Result<f64, ComputeError> {
+    require_len("values", values, 3)?;
+    let (m2, m3, _) = central_moments(values)?;
+    let n = values.len() as f64;
+    let g1 = m3 / m2.powf(1.5);
+    Ok(g1 * (n * (n - 1.0)).sqrt() / (n - 2.0))
+}
+
+/// Sample excess kurtosis G2 (as Excel `KURT` and SAS); 0 for a normal
+/// distribution.
+pub fn kurtosis(values: &[f64]) -> Result<f64, ComputeError> {
+    require_len("values", values, 4)?;
+    let (m2, _, m4) = central_moments(values)?;
+    let n = values.len() as f64;
+    let g2 = m4 / (m2 * m2) - 3.0;
+    Ok((n - 1.0) / ((n - 2.0) * (n - 3.0)) * ((n + 1.0) * g2 + 6.0))
+}
+
+/// Coefficient of variation: sample standard deviation over the absolute
+/// mean.
+pub fn coefficient_of_variation(values: &[f64]) -> Result<f64, ComputeError> {
+    let sd = std_dev(values)?;
+    let m = mean(values)?;
+    if m == 0.0 {
+        return Err(ComputeError::ZeroBase { arg: "values" });
+    }
+    Ok(sd / m.abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    const TYPES: [QuantileMethod; 9] = [
+        QuantileMethod::Type1,
+        QuantileMethod::Type2,
+        QuantileMethod::Type3,
+        QuantileMethod::Type4,
+        QuantileMethod::Type5,
+        QuantileMethod::Type6,
+        QuantileMethod::Type7,
+        QuantileMethod::Type8,
+        QuantileMethod::Type9,
+    ];
+
+    /// `quantile(x, p, type = 1:9)` in R.
+    fn check_quantiles(values: &[f64], p: f64, expected: [f64; 9]) {
+        for (method, expected) in TYPES.iter().zip(expected) {
+            let q = quantile(values, p, *method).unwrap();
+            assert!(
+                (q - expected).abs() < 1e-12,
+                "{method:?} at {p}: {q} != {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn quantile_types_match_r() {
+        let one_to_ten: Vec<f64> = (1..=10).map(f64::from).collect();
+        check_quantiles(
+            &one_to_ten,
+            0.25,
+            [3.0, 3.0, 2.0, 2.5, 3.0, 2.75, 3.25, 35.0 / 12.0, 2.9375],
+        );
+
+        let x = [8.0, 2.0, 15.0, 4.0, 23.0, 42.0, 16.0];
+        check_quantiles(
+            &x,
+            0.1,
+            [2.0, 2.0, 2.0, 2.0, 2.4, 2.0, 3.2, 32.0 / 15.0, 2.2],
+        );
+        check_quantiles(
+            &x,
+            0.25,
+            [4.0, 4.0, 4.0, 3.5, 5.0, 4.0, 6.0, 14.0 / 3.0, 4.75],
+        );
+        check_quantiles(
+            &x,
+            0.5,
+            [15.0, 15.0, 15.0, 11.5, 15.0, 15.0, 15.0, 15.0, 15.0],
+        );
+        check_quantiles(
+            &x,
+            0.9,
+            [42.0, 42.0, 23.0, 28.7, 38.2, 42.0, 30.6, 611.0 / 15.0, 40.1],
+        );
+    }
+
+    #[test]
+    fn quantile_extremes_are_min_and_max() {
+        let x = [3.0, 1.0, 2.0];
+        for method in TYPES {
+            assert_eq!(quantile(&x, 0.0, method).unwrap(), 1.0);
+            assert_eq!(quantile(&x, 1.0, method).unwrap(), 3.0);
+        }
+        assert_eq!(
+            quantile(&x, 1.5, QuantileMethod::Type7),
+            Err(ComputeError::OutOfRange {
+                arg: "p",
+                value: 1.5,
+                min: 0.0,
+                max: 1.0
+            })
+        );
+    }
+
+    #[test]
+    fn spread_measures() {
+        let x = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        assert_close(population_std_dev(&x).unwrap(), 2.0, 1e-12);
+        assert_close(variance(&x).unwrap(), 32.0 / 7.0, 1e-12);
+        assert_close(median(&x).unwrap(), 4.5, 1e-12);
+        assert_close(mad(&x).unwrap(), 0.5, 1e-12);
+        assert_close(iqr(&x, QuantileMethod::Type7).unwrap(), 1.5, 1e-12);
+        assert_close(
+            coefficient_of_variation(&x).unwrap(),
+            (32.0f64 / 7.0).sqrt() / 5.0,
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn skewness_and_kurtosis_match_excel() {
+        // The worked example in Excel's SKEW and KURT documentation.
+        let x = [3.0, 4.0, 5.0, 2.0, 3.0, 4.0, 5.0, 6.0, 4.0, 7.0];
+        assert_close(skewness(&x).unwrap(), 0.359543071, 1e-8);
+        assert_close(kurtosis(&x).unwrap(), -0.151799637, 1e-8);
+        assert_eq!(
+            skewness(&[1.0, 1.0, 1.0]),
+            Err(ComputeError::ZeroVariance { arg: "values" })
+        );
+    }
+}
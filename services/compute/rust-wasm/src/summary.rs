@@ -0,0 +1,165 @@
+use serde::Serialize;
+
+use crate::error::{require_same_len, ComputeError};
+use crate::series::YearSeries;
+use crate::{change, regression, stats};
+
+/// Every headline statistic for one indicator series.
+///
+/// Fields that cannot be computed for the given series (too few points,
+/// zero base, non-positive values for CAGR) are `None`, which reaches JS
+/// as `null`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Summary {
+    pub count: usize,
+    pub mean: Option<f64>,
+    pub median: Option<f64>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub first: Option<f64>,
+    pub last: Option<f64>,
+    pub first_year: Option<f64>,
+    pub last_year: Option<f64>,
+    pub pct_change: Option<f64>,
+    /// Year-aware trend slope, in units per year.
+    pub slope: Option<f64>,
+    /// Compound annual growth rate in percent per year.
+    pub cagr: Option<f64>,
+    /// Sample standard deviation.
+    pub std_dev: Option<f64>,
+}
+
+/// Compute every headline statistic for a year series. Each statistic
+/// reuses the shared helper for it, so the values are scanned several
+/// times; series are short enough that this costs nothing noticeable.
+pub fn summarize(series: &YearSeries) -> Summary {
+    let years = series.years();
+    let values = series.values();
+    if series.is_empty() {
+        return Summary::default();
+    }
+
+    let first_year = years[0];
+    let last_year = years[years.len() - 1];
+    let first = values[0];
+    let last = values[values.len() - 1];
+
+    Summary {
+        count: series.len(),
+        mean: stats::mean(values).ok(),
+        median: stats::median(values).ok(),
+        min: values.iter().copied().reduce(f64::min),
+        max: values.iter().copied().reduce(f64::max),
+        first: Some(first),
+        last: Some(last),
+        first_year: Some(first_year),
+        last_year: Some(last_year),
+        pct_change: change::pct_change(values).ok(),
+        slope: regression::linear_fit(years, values).ok().map(|f| f.slope),
+        cagr: change::cagr(first, last, last_year - first_year).ok(),
+        std_dev: stats::std_dev(values).ok(),
+    }
+}
+
+/// Summarize many series stored back to back: `lengths[i]` points of
+/// `years` and `values` belong to series `i`.
+pub fn summarize_batch(
+    years: &[f64],
+    values: &[f64],
+    lengths: &[u32],
+) -> Result<Vec<Summary>, ComputeError> {
+    require_same_len("years", years, values.len())?;
+    let total: usize = lengths.iter().map(|&len| len as usize).sum();
+    if total != values.len() {
+        return Err(ComputeError::LengthMismatch {
+            arg: "lengths",
+            expected: values.len(),
+            len: total,
+        });
+    }
+
+    let mut start = 0;
+    Ok(lengths
+        .iter()
+        .map(|&len| {
+            let end = start + len as usize;
+            let series = YearSeries::new(&years[start..end], &values[start..end]);
+            start = end;
+            summarize(&series)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    #[test]
+    fn summarizes_every_field() {
+        let series = YearSeries::new(&[2010.0, 2000.0, 2003.0], &[80.0, 50.0, 60.0]);
+        let summary = summarize(&series);
+        assert_eq!(summary.count, 3);
+        assert_close(summary.mean.unwrap(), 63.333_333_333_333_336, 1e-12);
+        assert_eq!(summary.median, Some(60.0));
+        assert_eq!(summary.min, Some(50.0));
+        assert_eq!(summary.max, Some(80.0));
+        assert_eq!((summary.first, summary.last), (Some(50.0), Some(80.0)));
+        assert_eq!(summary.first_year, Some(2000.0));
+        assert_eq!(summary.last_year, Some(2010.0));
+        assert_close(summary.pct_change.unwrap(), 60.0, 1e-12);
+        assert_close(summary.slope.unwrap(), 2.974_683_544_303_797_5, 1e-12);
+        assert_close(summary.cagr.unwrap(), 4.812_238_946_895_784, 1e-12);
+        assert_close(summary.std_dev.unwrap(), 15.275_252_316_519_467, 1e-12);
+    }
+
+    #[test]
+    fn batch_keeps_order_and_nulls_what_does_not_apply() {
+        let years = [2000.0, 2003.0, 2010.0, 2001.0, 2001.0, 2002.0];
+        let values = [50.0, 60.0, 80.0, 7.0, 0.0, -3.0];
+        let summaries = summarize_batch(&years, &values, &[3, 1, 0, 2]).unwrap();
+        assert_eq!(summaries.len(), 4);
+        assert_eq!(summaries[0].count, 3);
+        assert_close(summaries[0].slope.unwrap(), 2.974_683_544_303_797_5, 1e-12);
+
+        // One point: levels only.
+        let single = &summaries[1];
+        assert_eq!((single.count, single.mean), (1, Some(7.0)));
+        assert_eq!(
+            (single.pct_change, single.slope, single.std_dev),
+            (None, None, None)
+        );
+
+        // Empty: nothing at all.
+        assert_eq!(summaries[2].count, 0);
+        assert_eq!((summaries[2].mean, summaries[2].first), (None, None));
+
+        // Zero base and a negative end: no relative change or CAGR.
+        let invalid = &summaries[3];
+        assert_eq!(invalid.count, 2);
+        assert_eq!((invalid.pct_change, invalid.cagr), (None, None));
+        assert_close(invalid.slope.unwrap(), -3.0, 1e-12);
+    }
+
+    #[test]
+    fn batch_lengths_must_cover_the_input() {
+        let years = [2000.0, 2001.0, 2002.0];
+        let values = [1.0, 2.0, 3.0];
+        assert_eq!(
+            summarize_batch(&years, &values, &[1, 1]).unwrap_err(),
+            ComputeError::LengthMismatch {
+                arg: "lengths",
+                expected: 3,
+                len: 2
+            }
+        );
+        assert_eq!(
+            summarize_batch(&years[..2], &values, &[3]).unwrap_err(),
+            ComputeError::LengthMismatch {
+                arg: "years",
+                expected: 3,
+                len: 2
+            }
+        );
+    }
+}
@@ -0,0 +1,27 @@
+//! Shared fixtures for unit tests.
+
+use crate::series::YearSeries;
+
+/// R's `lh` dataset: luteinizing hormone in blood samples at 10-minute
+/// intervals, 48 observations.
+pub const LH: [f64; 48] = [
+    2.4, 2.4, 2.4, 2.2, 2.1, 1.5, 2.3, 2.3, 2.5, 2.0, 1.9, 1.7, 2.2, 1.8, 3.2, 3.2, 2.7, 2.2, 2.2,
+    1.9, 1.9, 1.8, 2.7, 3.0, 2.3, 2.0, 2.0, 2.9, 2.9, 2.7, 2.7, 2.3, 2.6, 2.4, 1.8, 1.7, 1.5, 1.4,
+    2.1, 3.3, 3.5, 3.5, 3.1, 2.6, 2.1, 3.4, 3.0, 2.9,
+];
+
+/// `values` as an annual series starting in 1970.
+pub fn annual(values: &[f64]) -> YearSeries {
+    let years: Vec<f64> = (0..values.len()).map(|i| 1970.0 + i as f64).collect();
+    YearSeries::new(&years, values)
+}
+
+/// Assert `actual` is within `tol` of `expected`, absolutely or relative
+/// to `expected`, whichever is looser.
+#[track_caller]
+pub fn assert_close(actual: f64, expected: f64, tol: f64) {
+    assert!(
+        (actual - expected).abs() <= tol * expected.abs().max(1.0),
+        "{actual} != {expected} (tol {tol})"
+    );
+}